@@ -0,0 +1,94 @@
+// How good is the guide, really? Against the opponent's column alone: the best anyone could
+// have done, what a mixed strategy scores on average, and how a player picking uniformly at
+// random would be spread out, so a guide's total can be put in context.
+
+use crate::ruleset::Ruleset;
+use crate::Play;
+
+pub fn opponents_column(input: &str, rules: &Ruleset) -> Result<Vec<Play>, String> {
+    input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(n, line)| {
+        let code = line.split(' ').next().unwrap_or_default();
+        rules.opponents_play(code).ok_or_else(|| format!("line {}: invalid opponent's play `{}`", n + 1, code))
+    }).collect()
+}
+
+// the highest scoring move every round, the earliest one on ties
+pub fn best_response(rules: &Ruleset, opponents: &[Play]) -> (Vec<Play>, usize) {
+    let plays: Vec<_> = opponents.iter().map(|&opponents_play| {
+        (0..rules.len()).map(Play).rev().max_by_key(|&play| rules.score(opponents_play, play)).unwrap()
+    }).collect();
+    let total = opponents.iter().zip(&plays).map(|(&opponents_play, &play)| rules.score(opponents_play, play)).sum();
+    (plays, total)
+}
+
+// `weights` has one entry per move and doesn't need to add up to 1
+pub fn expected_score(rules: &Ruleset, opponents: &[Play], weights: &[f64]) -> f64 {
+    assert_eq!(weights.len(), rules.len(), "need a weight for every move");
+    let sum: f64 = weights.iter().sum();
+    opponents.iter().map(|&opponents_play| {
+        weights.iter().enumerate().map(|(play, weight)| weight / sum * rules.score(opponents_play, Play(play)) as f64).sum::<f64>()
+    }).sum()
+}
+
+// probabilities[total] is the chance of a random player ending up with exactly that total
+pub struct Distribution {
+    probabilities: Vec<f64>,
+}
+
+impl Distribution {
+    pub fn min(&self) -> usize {
+        self.probabilities.iter().position(|&p| p > 0.0).unwrap_or(0)
+    }
+
+    pub fn max(&self) -> usize {
+        self.probabilities.iter().rposition(|&p| p > 0.0).unwrap_or(0)
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.probabilities.iter().enumerate().map(|(total, p)| total as f64 * p).sum()
+    }
+
+    // the chance of a random player scoring less than `total`
+    pub fn below(&self, total: usize) -> f64 {
+        self.probabilities.iter().take(total).sum()
+    }
+}
+
+// one round at a time, convolving the totals so far with the round's equally likely scores
+pub fn random_player(rules: &Ruleset, opponents: &[Play]) -> Distribution {
+    let share = 1.0 / rules.len() as f64;
+    let mut probabilities = vec![1.0];
+    for &opponents_play in opponents {
+        let scores: Vec<_> = (0..rules.len()).map(|play| rules.score(opponents_play, Play(play))).collect();
+        let mut next = vec![0.0; probabilities.len() + scores.iter().max().unwrap()];
+        for (total, &p) in probabilities.iter().enumerate().filter(|&(_, &p)| p > 0.0) {
+            for &score in &scores {
+                next[total + score] += p * share;
+            }
+        }
+        probabilities = next;
+    }
+    Distribution { probabilities }
+}
+
+#[test]
+fn test_analysis() {
+    let rules = Ruleset::classic();
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let opponents = opponents_column(&input, &rules).unwrap();
+
+    // paper beats rock for 8, scissors beats paper for 9, rock beats scissors for 7
+    let (plays, total) = best_response(&rules, &opponents);
+    assert_eq!(plays, [Play(1), Play(2), Play(0)]);
+    assert_eq!(total, 24);
+
+    assert!((expected_score(&rules, &opponents, &[1.0, 0.0, 0.0]) - 12.0).abs() < 1e-9);
+    assert!((expected_score(&rules, &opponents, &[1.0, 1.0, 1.0]) - 15.0).abs() < 1e-9);
+
+    let random = random_player(&rules, &opponents);
+    assert!((random.mean() - 15.0).abs() < 1e-9);
+    assert_eq!((random.min(), random.max()), (3 + 1 + 2, 24));
+    assert!((random.below(25) - 1.0).abs() < 1e-9);
+    // the best response is the only way to get 24
+    assert!((random.below(24) - (1.0 - 1.0 / 27.0)).abs() < 1e-9);
+}
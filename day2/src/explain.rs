@@ -0,0 +1,107 @@
+// Showing the working for a guide: every round with where its points came from and the total
+// so far, then how each of my moves fared. For checking a guide by eye, or by script as JSON.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::ruleset::Ruleset;
+use crate::{Outcome, Play};
+
+#[derive(Serialize)]
+pub struct Round {
+    pub opponent: String,
+    pub me: String,
+    pub outcome: Outcome,
+    pub shape_points: usize,
+    pub outcome_points: usize,
+    // including this round
+    pub total: usize,
+}
+
+#[derive(Serialize)]
+pub struct MoveRecord {
+    pub play: String,
+    pub won: usize,
+    pub lost: usize,
+    pub drawn: usize,
+}
+
+#[derive(Serialize)]
+pub struct Explanation {
+    pub interpretation: String,
+    pub rounds: Vec<Round>,
+    // every move, in the rules' order, whether I played it or not
+    pub moves: Vec<MoveRecord>,
+    pub total: usize,
+}
+
+pub fn explain(rules: &Ruleset, interpretation: &str, rounds: &[(Play, Play)]) -> Explanation {
+    let mut moves: Vec<_> = (0..rules.len()).map(|play| {
+        MoveRecord { play: rules.name(Play(play)).to_string(), won: 0, lost: 0, drawn: 0 }
+    }).collect();
+    let mut total = 0;
+
+    let rounds = rounds.iter().map(|&(opponents_play, my_play)| {
+        let outcome = rules.outcome(opponents_play, my_play);
+        let shape_points = rules.shape_points(my_play);
+        let outcome_points = rules.outcome_points(outcome);
+        total += shape_points + outcome_points;
+
+        let record = &mut moves[my_play.0];
+        match outcome {
+            Outcome::IWin => record.won += 1,
+            Outcome::ILose => record.lost += 1,
+            Outcome::Draw => record.drawn += 1,
+        }
+
+        Round {
+            opponent: rules.name(opponents_play).to_string(),
+            me: rules.name(my_play).to_string(),
+            outcome,
+            shape_points,
+            outcome_points,
+            total,
+        }
+    }).collect();
+
+    Explanation { interpretation: interpretation.to_string(), rounds, moves, total }
+}
+
+impl Explanation {
+    pub fn to_table(&self) -> String {
+        let mut table = String::new();
+        let width = self.moves.iter().map(|record| record.play.len()).max().unwrap_or(0).max(8);
+
+        writeln!(table, "interpretation {}", self.interpretation).unwrap();
+        writeln!(table, "{:>5} {:<width$} {:<width$} {:<7} {:>5} {:>7} {:>7}", "round", "opponent", "me", "outcome", "shape", "outcome", "total").unwrap();
+        for (n, round) in self.rounds.iter().enumerate() {
+            writeln!(table, "{:>5} {:<width$} {:<width$} {:<7} {:>5} {:>7} {:>7}",
+                n + 1, round.opponent, round.me, round.outcome.to_string(), round.shape_points, round.outcome_points, round.total).unwrap();
+        }
+
+        writeln!(table).unwrap();
+        writeln!(table, "{:<width$} {:>5} {:>5} {:>5}", "me", "won", "lost", "drew").unwrap();
+        for record in &self.moves {
+            writeln!(table, "{:<width$} {:>5} {:>5} {:>5}", record.play, record.won, record.lost, record.drawn).unwrap();
+        }
+        writeln!(table, "total {}", self.total).unwrap();
+        table
+    }
+}
+
+#[test]
+fn test_explain() {
+    let rules = Ruleset::classic();
+    let rounds = [(Play(0), Play(1)), (Play(1), Play(0)), (Play(2), Play(2))];
+    let explanation = explain(&rules, "a", &rounds);
+
+    assert_eq!(explanation.rounds.iter().map(|round| round.total).collect::<Vec<_>>(), [8, 9, 15]);
+    assert_eq!(explanation.total, 15);
+    assert_eq!((explanation.moves[0].lost, explanation.moves[1].won, explanation.moves[2].drawn), (1, 1, 1));
+
+    let json = serde_json::to_value(&explanation).unwrap();
+    assert_eq!(json["rounds"][1]["outcome"], "lose");
+    assert_eq!(json["rounds"][0]["me"], "Paper");
+    assert_eq!(json["moves"][2]["drawn"], 1);
+}
@@ -0,0 +1,139 @@
+// Working out what the second column means from the score it's supposed to give. Every way of
+// giving its codes different moves, and every way of giving them different outcomes, is tried.
+// Only the number of times each (opponent's move, code) pair comes up matters, so each guess
+// is scored without going back over the guide.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ruleset::Ruleset;
+use crate::{Outcome, Play};
+
+const OUTCOMES: [Outcome; 3] = [Outcome::ILose, Outcome::Draw, Outcome::IWin];
+
+pub enum Meaning {
+    Moves(Vec<Play>),
+    Outcomes(Vec<Outcome>),
+}
+
+// what each code stands for, in the same order as `codes`
+pub struct Inference<'a> {
+    pub codes: &'a [String],
+    pub meaning: Meaning,
+    rules: &'a Ruleset,
+}
+
+pub struct Guide {
+    // the distinct codes in the second column, sorted
+    pub codes: Vec<String>,
+    // (opponent's move, index into codes) -> how many times
+    rounds: HashMap<(Play, usize), usize>,
+}
+
+impl Guide {
+    pub fn parse(input: &str, rules: &Ruleset) -> Result<Self, String> {
+        let lines: Vec<(Play, &str)> = input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(n, line)| {
+            match line.split_once(' ') {
+                Some((opponents_code, my_code)) => {
+                    let play = rules.opponents_play(opponents_code).ok_or_else(|| format!("line {}: invalid opponent's play `{}`", n + 1, opponents_code))?;
+                    Ok((play, my_code))
+                }
+                None => Err(format!("line {}: expected two columns", n + 1)),
+            }
+        }).collect::<Result<_, _>>()?;
+
+        let mut codes: Vec<String> = lines.iter().map(|&(_, code)| code.to_string()).collect();
+        codes.sort();
+        codes.dedup();
+
+        let mut rounds = HashMap::new();
+        for (play, code) in lines {
+            let index = codes.iter().position(|other| other == code).unwrap();
+            *rounds.entry((play, index)).or_default() += 1;
+        }
+        Ok(Guide { codes, rounds })
+    }
+
+    pub fn score_moves(&self, rules: &Ruleset, moves: &[Play]) -> usize {
+        self.rounds.iter().map(|(&(opponents_play, code), &count)| count * rules.score(opponents_play, moves[code])).sum()
+    }
+
+    // None if some outcome can't be had against one of the opponent's moves
+    pub fn score_outcomes(&self, rules: &Ruleset, outcomes: &[Outcome]) -> Option<usize> {
+        self.rounds.iter().map(|(&(opponents_play, code), &count)| {
+            Some(count * rules.score(opponents_play, rules.play_for(opponents_play, outcomes[code])?))
+        }).sum()
+    }
+}
+
+pub fn infer<'a>(guide: &'a Guide, rules: &'a Ruleset, total: usize) -> Vec<Inference<'a>> {
+    let mut found = vec![];
+
+    for arrangement in arrangements(guide.codes.len(), rules.len()) {
+        let moves: Vec<_> = arrangement.into_iter().map(Play).collect();
+        if guide.score_moves(rules, &moves) == total {
+            found.push(Inference { codes: &guide.codes, meaning: Meaning::Moves(moves), rules });
+        }
+    }
+
+    for arrangement in arrangements(guide.codes.len(), OUTCOMES.len()) {
+        let outcomes: Vec<_> = arrangement.into_iter().map(|i| OUTCOMES[i]).collect();
+        if guide.score_outcomes(rules, &outcomes) == Some(total) {
+            found.push(Inference { codes: &guide.codes, meaning: Meaning::Outcomes(outcomes), rules });
+        }
+    }
+
+    found
+}
+
+// every way of picking k of 0..n in order, without repeats
+fn arrangements(k: usize, n: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if k > n {
+        return vec![];
+    }
+    let mut found = vec![];
+    for prefix in arrangements(k - 1, n) {
+        for i in (0..n).filter(|i| !prefix.contains(i)) {
+            let mut arrangement = prefix.clone();
+            arrangement.push(i);
+            found.push(arrangement);
+        }
+    }
+    found
+}
+
+impl fmt::Display for Inference<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let meanings: Vec<String> = match &self.meaning {
+            Meaning::Moves(moves) => moves.iter().map(|&play| self.rules.name(play).to_string()).collect(),
+            Meaning::Outcomes(outcomes) => outcomes.iter().map(Outcome::to_string).collect(),
+        };
+        let kind = match self.meaning {
+            Meaning::Moves(_) => "moves",
+            Meaning::Outcomes(_) => "outcomes",
+        };
+        let pairs: Vec<_> = self.codes.iter().zip(meanings).map(|(code, meaning)| format!("{}={}", code, meaning)).collect();
+        write!(f, "{}: {}", kind, pairs.join(" "))
+    }
+}
+
+#[test]
+fn test_infer() {
+    let rules = Ruleset::classic();
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let guide = Guide::parse(&input, &rules).unwrap();
+
+    let found: Vec<_> = infer(&guide, &rules, 15).iter().map(Inference::to_string).collect();
+    assert!(found.contains(&"moves: X=Rock Y=Paper Z=Scissors".to_string()));
+    let found: Vec<_> = infer(&guide, &rules, 12).iter().map(Inference::to_string).collect();
+    assert!(found.contains(&"outcomes: X=lose Y=draw Z=win".to_string()));
+
+    // each of the 6 move orders and 6 outcome orders turns up under exactly one total
+    let totals: usize = (0..=30).map(|total| infer(&guide, &rules, total).len()).sum();
+    assert_eq!(totals, 12);
+    assert_eq!(arrangements(2, 3).len(), 6);
+    assert!(Guide::parse("Q X", &rules).is_err());
+}
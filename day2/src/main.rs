@@ -1,150 +1,228 @@
-use std::env;
+mod analysis;
+mod explain;
+mod infer;
+mod ruleset;
+mod tournament;
+
+use std::{env, fmt, process};
+
+use serde::{Deserialize, Serialize};
+
+use crate::infer::Guide;
 use crate::Outcome::{Draw, ILose, IWin};
-use crate::Play::{Paper, Rock, Scissors};
+use crate::ruleset::Ruleset;
+use crate::tournament::{Always, CopyLast, Cycle, FrequencyCounter, GuideFollower, Strategy};
 
-#[derive(Copy, Clone)]
-enum Play {
-    Rock,
-    Paper,
-    Scissors,
-}
+// which of the ruleset's moves, counting from 0
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Play(usize);
 
-#[derive(Copy, Clone)]
-enum Outcome {
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Outcome {
+    #[serde(rename = "win")]
     IWin,
+    #[serde(rename = "lose")]
     ILose,
+    #[serde(rename = "draw")]
     Draw,
 }
 
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IWin => write!(f, "win"),
+            ILose => write!(f, "lose"),
+            Draw => write!(f, "draw"),
+        }
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let filename = &args[1];
 
+    // --rules=<toml or json file>, the puzzle's rules otherwise
+    let rules = match args.iter().find_map(|arg| arg.strip_prefix("--rules=")) {
+        Some(path) => Ruleset::load(path).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        }),
+        None => Ruleset::classic(),
+    };
+    let json = args.iter().any(|arg| arg == "--json");
+    let args: Vec<_> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    let filename = args[1];
     let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", a(input));
 
-    let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", b(input));
-}
+    let result = match args.get(2).map(|arg| arg.as_str()) {
+        // day2 <file> infer <total>
+        Some("infer") => print_inferences(&input, &rules, args[3].parse().unwrap()),
+        // day2 <file> analyse [weight per move...]
+        Some("analyse") => {
+            let weights = match args.get(3..) {
+                Some(weights) if !weights.is_empty() => weights.iter().map(|weight| {
+                    weight.parse::<f64>().map_err(|_| format!("`{}` isn't a weight", weight))
+                }).collect(),
+                _ => Ok(vec![1.0; rules.len()]),
+            };
+            weights.and_then(|weights| print_analysis(&input, &rules, &weights))
+        }
+        // day2 <file> tournament [rounds]
+        Some("tournament") => print_tournament(&input, &rules, args.get(3).map_or(1000, |rounds| rounds.parse().unwrap())),
+        // day2 <file> explain [interpretation...] [--json]
+        Some("explain") => print_explanations(&input, &rules, &interpretation_names(&rules, &args[3..]), json),
+        // day2 <file> [interpretation...]
+        _ => {
+            interpretation_names(&rules, &args[2..]).into_iter().try_for_each(|name| {
+                println!("{}", score(&input, &rules, name)?);
+                Ok(())
+            })
+        }
+    };
 
-fn get_play_a(play_code: &str) -> Play {
-    // could use TryFrom trait on the Play struct
-    match play_code {
-        "A" => Rock,
-        "B" => Paper,
-        "C" => Scissors,
-        "X" => Rock,
-        "Y" => Paper,
-        "Z" => Scissors,
-        _ => panic!("Invalid play code")
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
     }
 }
 
-fn get_plays_b(opponents_play_code: &str, my_outcome_code: &str) -> (Play, Play) {
-    let opponents_play = match opponents_play_code {
-        "A" => Rock,
-        "B" => Paper,
-        "C" => Scissors,
-        _ => panic!("Invalid opponent's play code")
-    };
+// every one in the rules (so a then b) if none are given
+fn interpretation_names<'a>(rules: &'a Ruleset, args: &'a [&String]) -> Vec<&'a str> {
+    if args.is_empty() {
+        rules.interpretations().iter().map(|interpretation| interpretation.name.as_str()).collect()
+    } else {
+        args.iter().map(|name| name.as_str()).collect()
+    }
+}
 
-    let my_outcome = match my_outcome_code {
-        "X" => ILose,
-        "Y" => Draw,
-        "Z" => IWin,
-        _ => panic!("Invalid outcome code")
-    };
+// as JSON, a list with one explanation per interpretation
+fn print_explanations(input: &str, rules: &Ruleset, names: &[&str], json: bool) -> Result<(), String> {
+    let explanations = names.iter().map(|&name| {
+        Ok(explain::explain(rules, name, &get_rounds(input, rules, name)?))
+    }).collect::<Result<Vec<_>, String>>()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&explanations).unwrap());
+    } else {
+        let tables: Vec<_> = explanations.iter().map(|explanation| explanation.to_table()).collect();
+        print!("{}", tables.join("\n"));
+    }
+    Ok(())
+}
 
-    match (opponents_play, my_outcome) {
-        // could factor out into what beats what with 3 items
-        (Rock, ILose) => (Rock, Scissors),
-        (Rock, Draw) => (Rock, Rock),
-        (Rock, IWin) => (Rock, Paper),
-        (Paper, ILose) => (Paper, Rock),
-        (Paper, Draw) => (Paper, Paper),
-        (Paper, IWin) => (Paper, Scissors),
-        (Scissors, ILose) => (Scissors, Paper),
-        (Scissors, Draw) => (Scissors, Scissors),
-        (Scissors, IWin) => (Scissors, Rock),
+fn print_inferences(input: &str, rules: &Ruleset, total: usize) -> Result<(), String> {
+    let guide = Guide::parse(input, rules)?;
+    let found = infer::infer(&guide, rules, total);
+    if found.is_empty() {
+        println!("nothing gives {}", total);
+    }
+    for inference in found {
+        println!("{}", inference);
     }
+    Ok(())
 }
 
-fn get_match_outcome(opponents_play: Play, my_play: Play) -> Outcome {
-    match (opponents_play, my_play) {
-        (Rock, Paper) => IWin,
-        (Rock, Scissors) => ILose,
-        (Paper, Rock) => ILose,
-        (Paper, Scissors) => IWin,
-        (Scissors, Rock) => IWin,
-        (Scissors, Paper) => ILose,
-        _ => Draw,
+fn print_analysis(input: &str, rules: &Ruleset, weights: &[f64]) -> Result<(), String> {
+    if weights.len() != rules.len() {
+        return Err(format!("{} moves but {} weights", rules.len(), weights.len()));
+    }
+    if let Some(weight) = weights.iter().find(|&&weight| weight < 0.0 || !weight.is_finite()) {
+        return Err(format!("weights have to be finite and not negative, got {}", weight));
+    }
+    if weights.iter().sum::<f64>() == 0.0 {
+        return Err("at least one weight has to be more than 0".to_string());
     }
+    let opponents = analysis::opponents_column(input, rules)?;
+
+    let (plays, best) = analysis::best_response(rules, &opponents);
+    let plays: Vec<_> = plays.iter().map(|&play| rules.name(play)).collect();
+    println!("best response: {}", best);
+    println!("{}", plays.join(" "));
+    println!("mixed strategy: {:.2} expected", analysis::expected_score(rules, &opponents, weights));
+
+    let random = analysis::random_player(rules, &opponents);
+    println!("random player: {:.2} on average, {} to {}", random.mean(), random.min(), random.max());
+    for interpretation in rules.interpretations() {
+        let total = score(input, rules, &interpretation.name)?;
+        println!("{}: {}, better than {:.1}% of random players", interpretation.name, total, 100.0 * random.below(total));
+    }
+    Ok(())
 }
 
-fn a(input: String) -> usize {
-    let mut my_score = 0;
-
-    for line in input.split("\n") {
-        let mut plays: Vec<_> = line.split(' ').map(get_play_a).collect();
-
-        assert!(plays.len() >= 2);
-        // could use iter::next rather than Vec::remove
-        let opponents_play = plays.remove(0);
-        let my_play = plays.remove(0);
-
-        let outcome = get_match_outcome(opponents_play, my_play);
-        my_score += match outcome {
-            IWin => 6,
-            ILose => 0,
-            Draw => 3,
-        };
-
-        // could put bonus points on the Move struct itself
-        my_score += match my_play {
-            Rock => 1,
-            Paper => 2,
-            Scissors => 3,
+// the guide is there for the guide followers, one for each way of reading it that works
+fn print_tournament(input: &str, rules: &Ruleset, rounds: usize) -> Result<(), String> {
+    let mut strategies: Vec<Box<dyn Strategy>> = (0..rules.len()).map(|play| Box::new(Always(Play(play))) as Box<dyn Strategy>).collect();
+    strategies.push(Box::new(Cycle));
+    strategies.push(Box::new(CopyLast));
+    strategies.push(Box::new(FrequencyCounter));
+    for interpretation in rules.interpretations() {
+        match get_rounds(input, rules, &interpretation.name) {
+            Ok(rounds) if rounds.is_empty() => eprintln!("leaving out guide {}: there are no rounds to follow", interpretation.name),
+            Ok(rounds) => {
+                let plays = rounds.into_iter().map(|(_, my_play)| my_play).collect();
+                strategies.push(Box::new(GuideFollower { name: interpretation.name.clone(), plays }));
+            }
+            Err(err) => eprintln!("leaving out guide {}: {}", interpretation.name, err),
         }
     }
 
-    my_score
+    let standings = tournament::round_robin(rules, &strategies, rounds);
+    let width = standings.iter().map(|standing| standing.name.len()).max().unwrap_or(0);
+    println!("{:<width$} {:>8} {:>4} {:>4} {:>4}", "strategy", "score", "won", "lost", "drew");
+    for standing in standings {
+        println!("{:<width$} {:>8} {:>4} {:>4} {:>4}", standing.name, standing.score, standing.won, standing.lost, standing.drawn);
+    }
+    Ok(())
 }
 
-fn b(input: String) -> usize {
-    let mut my_score = 0;
-
-    for line in input.split("\n") {
-        let mut codes: Vec<_> = line.split(' ').collect();
-
-        assert!(codes.len() >= 2);
-        let opponents_play_code = codes.remove(0);
-        let my_outcome_code = codes.remove(0);
-        let (opponents_play, my_play) = get_plays_b(&opponents_play_code, &my_outcome_code);
-
-        let outcome = get_match_outcome(opponents_play, my_play);
-        my_score += match outcome {
-            IWin => 6,
-            ILose => 0,
-            Draw => 3,
-        };
-        my_score += match my_play {
-            Rock => 1,
-            Paper => 2,
-            Scissors => 3,
-        }
-    }
+// (opponent's play, my play) for each line of the guide
+fn get_rounds(input: &str, rules: &Ruleset, interpretation: &str) -> Result<Vec<(Play, Play)>, String> {
+    let interpretation = rules.interpretation(interpretation).ok_or_else(|| format!("no interpretation called `{}`", interpretation))?;
 
-    my_score
+    // blank lines, like the one after a trailing newline, aren't rounds
+    input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(n, line)| {
+        let (opponents_code, my_code) = line.split_once(' ').ok_or_else(|| format!("line {}: expected two columns", n + 1))?;
+        rules.round(interpretation, opponents_code, my_code)
+            .ok_or_else(|| format!("line {}: can't read `{}` as interpretation {}", n + 1, line, interpretation.name))
+    }).collect()
+}
+
+// the total for following the guide, read the way the named interpretation says
+fn score(input: &str, rules: &Ruleset, interpretation: &str) -> Result<usize, String> {
+    let rounds = get_rounds(input, rules, interpretation)?;
+    Ok(rounds.into_iter().map(|(opponents_play, my_play)| rules.score(opponents_play, my_play)).sum())
 }
 
 #[test]
 fn test_example_a() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
-    assert_eq!(a(input), 15);
+    assert_eq!(score(&input, &Ruleset::classic(), "a"), Ok(15));
 }
 
 #[test]
 fn test_example_b() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
-    assert_eq!(b(input), 12);
-}
\ No newline at end of file
+    assert_eq!(score(&input, &Ruleset::classic(), "b"), Ok(12));
+}
+
+#[test]
+fn test_names_and_rpsls() {
+    assert_eq!(score("Rock Paper\nPaper Rock\nScissors Scissors", &Ruleset::classic(), "a"), Ok(15));
+    assert!(score("A Y", &Ruleset::classic(), "c").is_err());
+    assert_eq!(score("A Y\nB X\n\nC Z\n", &Ruleset::classic(), "b"), Ok(12));
+    assert!(print_analysis("A Y", &Ruleset::classic(), &[0.0, 0.0, 0.0]).is_err());
+    assert!(print_analysis("A Y", &Ruleset::classic(), &[1.0, -1.0, 1.0]).is_err());
+    assert!(print_analysis("A Y", &Ruleset::classic(), &[f64::INFINITY, 1.0, 1.0]).is_err());
+
+    let rules = Ruleset::load("src/rpsls.toml").unwrap();
+    // spock beats rock (6 + 2), lizard loses to rock (0 + 4), then win and lose against E, scissors
+    assert_eq!(score("A B\nA D", &rules, "a"), Ok(12));
+    assert_eq!(score("E Z\nE X", &rules, "b"), Ok(6 + 2 + 4));
+}
+
+#[test]
+fn test_empty_guide() {
+    // nothing for the guide followers to follow, so they sit it out
+    assert_eq!(score("\n\n", &Ruleset::classic(), "a"), Ok(0));
+    assert!(print_tournament("", &Ruleset::classic(), 10).is_ok());
+    assert!(print_tournament("\n", &Ruleset::classic(), 10).is_ok());
+}
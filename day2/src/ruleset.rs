@@ -0,0 +1,323 @@
+// Which moves there are, what each is worth, what beats what, and how to read the strategy
+// guide. The puzzle's rock paper scissors is just the default, see rules.toml.
+//
+// Without an explicit `beats` table the moves are taken to be in a circle where each one beats
+// the half of the others just before it. For three moves that's the usual rules, and for
+// Rock, Spock, Paper, Lizard, Scissors it's RPSLS.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::{Outcome, Play};
+
+const DEFAULT_RULES: &str = include_str!("rules.toml");
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    moves: Vec<String>,
+    points: Option<Vec<usize>>,
+    // move name -> the moves it beats
+    beats: Option<HashMap<String, Vec<String>>>,
+    outcome_points: Option<OutcomePoints>,
+    // code -> move name
+    opponent: Option<HashMap<String, String>>,
+    interpretations: Option<Vec<InterpretationConfig>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InterpretationConfig {
+    name: String,
+    // exactly one of these
+    moves: Option<HashMap<String, String>>,
+    outcomes: Option<HashMap<String, Outcome>>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutcomePoints {
+    pub win: usize,
+    pub draw: usize,
+    pub lose: usize,
+}
+
+// one way of reading the second column of the guide
+pub struct Interpretation {
+    pub name: String,
+    column: Column,
+}
+
+enum Column {
+    Moves(HashMap<String, Play>),
+    Outcomes(HashMap<String, Outcome>),
+}
+
+pub struct Ruleset {
+    names: Vec<String>,
+    points: Vec<usize>,
+    // beats[i][j]: move i beats move j
+    beats: Vec<Vec<bool>>,
+    outcome_points: OutcomePoints,
+    opponent: HashMap<String, Play>,
+    interpretations: Vec<Interpretation>,
+}
+
+impl Ruleset {
+    pub fn classic() -> Self {
+        Self::from_toml(DEFAULT_RULES).expect("default rules are broken")
+    }
+
+    // .json files are read as JSON, anything else as TOML
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        let rules = if path.ends_with(".json") { Self::from_json(&text) } else { Self::from_toml(&text) };
+        rules.map_err(|err| format!("{}: {}", path, err))
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        Self::from_config(toml::from_str(text).map_err(|err| err.to_string())?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        Self::from_config(serde_json::from_str(text).map_err(|err| err.to_string())?)
+    }
+
+    fn from_config(config: Config) -> Result<Self, String> {
+        let names = config.moves;
+        let n = names.len();
+        if n == 0 {
+            return Err("there has to be at least one move".to_string());
+        }
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].iter().any(|other| other.eq_ignore_ascii_case(name)) {
+                return Err(format!("`{}` is listed twice", name));
+            }
+        }
+        let find = |name: &str| find(&names, name).ok_or_else(|| format!("`{}` isn't one of the moves", name));
+
+        let points = config.points.unwrap_or_else(|| (1..=n).collect());
+        if points.len() != n {
+            return Err(format!("{} moves but {} points", n, points.len()));
+        }
+
+        let beats = match config.beats {
+            None => cyclic_beats(n),
+            Some(table) => {
+                let mut beats = vec![vec![false; n]; n];
+                for (winner, losers) in &table {
+                    let winner = find(winner)?;
+                    for loser in losers {
+                        beats[winner.0][find(loser)?.0] = true;
+                    }
+                }
+
+                // a move beating itself counts as beating each other too
+                let clash = (0..n).flat_map(|i| (0..=i).map(move |j| (i, j))).find(|&(i, j)| beats[i][j] && beats[j][i]);
+                match clash {
+                    Some((i, j)) if i == j => return Err(format!("`{}` can't beat itself", names[i])),
+                    Some((i, j)) => return Err(format!("`{}` and `{}` both beat each other", names[i], names[j])),
+                    None => {}
+                }
+                beats
+            }
+        };
+
+        let letters = || (0..n).map(|i| (((b'A' + i as u8) as char).to_string(), Play(i)));
+        let opponent = match config.opponent {
+            None => letters().collect(),
+            Some(codes) => codes.iter().map(|(code, name)| Ok((code.clone(), find(name)?))).collect::<Result<_, String>>()?,
+        };
+
+        let interpretations = match config.interpretations {
+            // the puzzle's X, Y and Z still mean the first three moves, so any ruleset can read its guide
+            None => vec![
+                Interpretation {
+                    name: "a".to_string(),
+                    column: Column::Moves(letters().chain(["X", "Y", "Z"].iter().take(n).enumerate().map(|(i, code)| (code.to_string(), Play(i)))).collect()),
+                },
+                Interpretation {
+                    name: "b".to_string(),
+                    column: Column::Outcomes(HashMap::from([
+                        ("X".to_string(), Outcome::ILose),
+                        ("Y".to_string(), Outcome::Draw),
+                        ("Z".to_string(), Outcome::IWin),
+                    ])),
+                },
+            ],
+            Some(configs) => {
+                let mut interpretations: Vec<Interpretation> = vec![];
+                for config in configs {
+                    let column = match (config.moves, config.outcomes) {
+                        (Some(codes), None) => Column::Moves(
+                            codes.iter().map(|(code, name)| Ok((code.clone(), find(name)?))).collect::<Result<_, String>>()?,
+                        ),
+                        (None, Some(codes)) => Column::Outcomes(codes),
+                        _ => return Err(format!("interpretation `{}` needs either `moves` or `outcomes`", config.name)),
+                    };
+                    if interpretations.iter().any(|other| other.name == config.name) {
+                        return Err(format!("interpretation `{}` is listed twice", config.name));
+                    }
+                    interpretations.push(Interpretation { name: config.name, column });
+                }
+                interpretations
+            }
+        };
+
+        Ok(Ruleset {
+            names,
+            points,
+            beats,
+            outcome_points: config.outcome_points.unwrap_or(OutcomePoints { win: 6, draw: 3, lose: 0 }),
+            opponent,
+            interpretations,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, play: Play) -> &str {
+        &self.names[play.0]
+    }
+
+    pub fn interpretations(&self) -> &[Interpretation] {
+        &self.interpretations
+    }
+
+    pub fn interpretation(&self, name: &str) -> Option<&Interpretation> {
+        self.interpretations.iter().find(|interpretation| interpretation.name == name)
+    }
+
+    pub fn outcome(&self, opponents_play: Play, my_play: Play) -> Outcome {
+        if self.beats[my_play.0][opponents_play.0] {
+            Outcome::IWin
+        } else if self.beats[opponents_play.0][my_play.0] {
+            Outcome::ILose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    pub fn shape_points(&self, play: Play) -> usize {
+        self.points[play.0]
+    }
+
+    pub fn outcome_points(&self, outcome: Outcome) -> usize {
+        match outcome {
+            Outcome::IWin => self.outcome_points.win,
+            Outcome::Draw => self.outcome_points.draw,
+            Outcome::ILose => self.outcome_points.lose,
+        }
+    }
+
+    pub fn score(&self, opponents_play: Play, my_play: Play) -> usize {
+        self.outcome_points(self.outcome(opponents_play, my_play)) + self.shape_points(my_play)
+    }
+
+    // move names work in either column as well as the codes
+    pub fn opponents_play(&self, code: &str) -> Option<Play> {
+        self.opponent.get(code).copied().or_else(|| find(&self.names, code))
+    }
+
+    // both plays of a line of the guide, read the given way
+    pub fn round(&self, interpretation: &Interpretation, opponents_code: &str, my_code: &str) -> Option<(Play, Play)> {
+        let opponents_play = self.opponents_play(opponents_code)?;
+        let my_play = match &interpretation.column {
+            Column::Moves(codes) => codes.get(my_code).copied().or_else(|| find(&self.names, my_code))?,
+            Column::Outcomes(codes) => self.play_for(opponents_play, *codes.get(my_code)?)?,
+        };
+        Some((opponents_play, my_play))
+    }
+
+    // when more than one move would do, the one worth the most points, then the earliest
+    pub fn play_for(&self, opponents_play: Play, outcome: Outcome) -> Option<Play> {
+        (0..self.len()).map(Play)
+            .filter(|&play| self.outcome(opponents_play, play) == outcome)
+            .min_by_key(|&play| (std::cmp::Reverse(self.points[play.0]), play.0))
+    }
+}
+
+fn find(names: &[String], name: &str) -> Option<Play> {
+    names.iter().position(|other| other.eq_ignore_ascii_case(name)).map(Play)
+}
+
+fn cyclic_beats(n: usize) -> Vec<Vec<bool>> {
+    let mut beats = vec![vec![false; n]; n];
+    for (i, row) in beats.iter_mut().enumerate() {
+        for d in 1..=n.saturating_sub(1) / 2 {
+            row[(i + n - d) % n] = true;
+        }
+    }
+    beats
+}
+
+#[test]
+fn test_rpsls() {
+    let rules = Ruleset::load("src/rpsls.toml").unwrap();
+    let play = |name| rules.opponents_play(name).unwrap();
+
+    assert_eq!(rules.outcome(play("Scissors"), play("Rock")), Outcome::IWin);
+    assert_eq!(rules.outcome(play("Lizard"), play("Rock")), Outcome::IWin);
+    assert_eq!(rules.outcome(play("Spock"), play("Lizard")), Outcome::IWin);
+    assert_eq!(rules.outcome(play("Spock"), play("Rock")), Outcome::ILose);
+    assert_eq!(rules.outcome(play("paper"), play("Paper")), Outcome::Draw);
+    // both Lizard and Scissors beat Paper, Scissors is worth more
+    assert_eq!(rules.play_for(play("Paper"), Outcome::IWin), Some(play("Scissors")));
+    assert_eq!(play("E"), play("Scissors"));
+    assert_eq!(rules.opponents_play("F"), None);
+
+    // the puzzle's guide still reads: X, Y and Z are rock, spock and paper
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    assert_eq!(crate::score(&input, &rules, "a"), Ok(8 + 1 + 6));
+    assert_eq!(crate::score(&input, &rules, "b"), Ok(4 + 5 + 11));
+
+    // spelling out the classic rules gives the same as the circle
+    let explicit = Ruleset::from_toml(r#"
+        moves = ["Rock", "Paper", "Scissors"]
+        points = [1, 2, 3]
+        beats = { Rock = ["Scissors"], Paper = ["Rock"], Scissors = ["Paper"] }
+    "#).unwrap();
+    let classic = Ruleset::classic();
+    for (i, j) in [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+        assert_eq!(explicit.outcome(Play(i), Play(j)), classic.outcome(Play(i), Play(j)));
+    }
+
+    assert!(Ruleset::from_toml(r#"moves = ["Rock", "Paper"]
+        beats = { Rock = ["Paper"], Paper = ["Rock"] }"#).is_err());
+    assert!(Ruleset::from_toml(r#"moves = ["Rock", "rock"]"#).is_err());
+}
+
+#[test]
+fn test_rules_file() {
+    // the same rules in JSON, with the codes and points swapped round
+    let rules = Ruleset::from_json(r#"{
+        "moves": ["Rock", "Paper", "Scissors"],
+        "points": [3, 2, 1],
+        "outcome_points": { "win": 10, "draw": 5, "lose": 1 },
+        "opponent": { "R": "Rock", "P": "Paper", "S": "Scissors" },
+        "interpretations": [
+            { "name": "mine", "moves": { "1": "Rock", "2": "Paper", "3": "Scissors" } },
+            { "name": "result", "outcomes": { "L": "lose", "D": "draw", "W": "win" } }
+        ]
+    }"#).unwrap();
+
+    let mine = rules.interpretation("mine").unwrap();
+    assert_eq!(rules.round(mine, "R", "2"), Some((Play(0), Play(1))));
+    assert_eq!(rules.round(mine, "R", "Scissors"), Some((Play(0), Play(2))));
+    assert_eq!(rules.round(mine, "A", "2"), None);
+    let result = rules.interpretation("result").unwrap();
+    assert_eq!(rules.round(result, "S", "W"), Some((Play(2), Play(0))));
+    assert_eq!(rules.score(Play(2), Play(0)), 10 + 3);
+    assert_eq!(rules.score(Play(0), Play(2)), 1 + 1);
+
+    assert!(Ruleset::from_toml(r#"moves = ["Rock"]
+        [[interpretations]]
+        name = "both"
+        moves = { X = "Rock" }
+        outcomes = { X = "win" }"#).is_err());
+    assert!(Ruleset::from_toml(r#"moves = ["Rock"]
+        opponent = { A = "Paper" }"#).is_err());
+}
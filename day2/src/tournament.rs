@@ -0,0 +1,151 @@
+// Pitting scripted players against each other. Every strategy plays every other one for the
+// same number of rounds, scored by the rules as usual, and the standings add up each
+// strategy's points across all its matches.
+
+use crate::ruleset::Ruleset;
+use crate::{Outcome, Play};
+
+// strategies only see what has been played so far, so the same one can be in any number of matches
+pub trait Strategy {
+    fn name(&self, rules: &Ruleset) -> String;
+    fn next(&self, rules: &Ruleset, mine: &[Play], theirs: &[Play]) -> Play;
+}
+
+pub struct Always(pub Play);
+
+impl Strategy for Always {
+    fn name(&self, rules: &Ruleset) -> String {
+        format!("always {}", rules.name(self.0).to_lowercase())
+    }
+
+    fn next(&self, _: &Ruleset, _: &[Play], _: &[Play]) -> Play {
+        self.0
+    }
+}
+
+// every move in turn
+pub struct Cycle;
+
+impl Strategy for Cycle {
+    fn name(&self, _: &Ruleset) -> String {
+        "cycle".to_string()
+    }
+
+    fn next(&self, rules: &Ruleset, mine: &[Play], _: &[Play]) -> Play {
+        Play(mine.len() % rules.len())
+    }
+}
+
+// whatever the opponent played last, starting with the first move
+pub struct CopyLast;
+
+impl Strategy for CopyLast {
+    fn name(&self, _: &Ruleset) -> String {
+        "copy last".to_string()
+    }
+
+    fn next(&self, _: &Ruleset, _: &[Play], theirs: &[Play]) -> Play {
+        theirs.last().copied().unwrap_or(Play(0))
+    }
+}
+
+// beats whatever the opponent has played most, the earliest move on ties
+pub struct FrequencyCounter;
+
+impl Strategy for FrequencyCounter {
+    fn name(&self, _: &Ruleset) -> String {
+        "frequency counter".to_string()
+    }
+
+    fn next(&self, rules: &Ruleset, _: &[Play], theirs: &[Play]) -> Play {
+        let mut counts = vec![0; rules.len()];
+        for play in theirs {
+            counts[play.0] += 1;
+        }
+        let favourite = (0..rules.len()).rev().max_by_key(|&play| counts[play]).map(Play).unwrap();
+        rules.play_for(favourite, Outcome::IWin).unwrap_or(favourite)
+    }
+}
+
+// my moves from a strategy guide, over and over
+pub struct GuideFollower {
+    pub name: String,
+    pub plays: Vec<Play>,
+}
+
+impl Strategy for GuideFollower {
+    fn name(&self, _: &Ruleset) -> String {
+        format!("guide {}", self.name)
+    }
+
+    fn next(&self, _: &Ruleset, mine: &[Play], _: &[Play]) -> Play {
+        self.plays[mine.len() % self.plays.len()]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub score: usize,
+    // matches, going by which player scored more
+    pub won: usize,
+    pub lost: usize,
+    pub drawn: usize,
+}
+
+// both players' totals
+pub fn play_match(rules: &Ruleset, first: &dyn Strategy, second: &dyn Strategy, rounds: usize) -> (usize, usize) {
+    let mut first_plays = vec![];
+    let mut second_plays = vec![];
+    let (mut first_score, mut second_score) = (0, 0);
+
+    for _ in 0..rounds {
+        let first_play = first.next(rules, &first_plays, &second_plays);
+        let second_play = second.next(rules, &second_plays, &first_plays);
+        first_score += rules.score(second_play, first_play);
+        second_score += rules.score(first_play, second_play);
+        first_plays.push(first_play);
+        second_plays.push(second_play);
+    }
+
+    (first_score, second_score)
+}
+
+// best first
+pub fn round_robin(rules: &Ruleset, strategies: &[Box<dyn Strategy>], rounds: usize) -> Vec<Standing> {
+    let mut standings: Vec<_> = strategies.iter().map(|strategy| {
+        Standing { name: strategy.name(rules), score: 0, won: 0, lost: 0, drawn: 0 }
+    }).collect();
+
+    for i in 0..strategies.len() {
+        for j in i + 1..strategies.len() {
+            let (first, second) = play_match(rules, strategies[i].as_ref(), strategies[j].as_ref(), rounds);
+            standings[i].score += first;
+            standings[j].score += second;
+            match first.cmp(&second) {
+                std::cmp::Ordering::Greater => (standings[i].won += 1, standings[j].lost += 1),
+                std::cmp::Ordering::Less => (standings[i].lost += 1, standings[j].won += 1),
+                std::cmp::Ordering::Equal => (standings[i].drawn += 1, standings[j].drawn += 1),
+            };
+        }
+    }
+
+    standings.sort_by(|a, b| b.score.cmp(&a.score).then(b.won.cmp(&a.won)));
+    standings
+}
+
+#[test]
+fn test_tournament() {
+    let rules = Ruleset::classic();
+    let rock = Play(0);
+
+    // copy last follows always rock after the first round and draws from then on
+    assert_eq!(play_match(&rules, &Always(rock), &CopyLast, 10), (4 * 10, 4 * 10));
+    // with nothing seen yet frequency counter goes for beating rock, and keeps at it
+    assert_eq!(play_match(&rules, &Always(rock), &FrequencyCounter, 10), (10, 8 * 10));
+
+    let strategies: Vec<Box<dyn Strategy>> = vec![Box::new(Always(rock)), Box::new(Cycle), Box::new(FrequencyCounter)];
+    let standings = round_robin(&rules, &strategies, 30);
+    assert_eq!(standings[0].name, "frequency counter");
+    assert_eq!(standings.iter().map(|standing| standing.won + standing.lost + standing.drawn).sum::<usize>(), 6);
+}
@@ -0,0 +1,118 @@
+// Part b with the rucksacks out of order: split them into groups of k that each have exactly
+// one item in common, the way the elves must have been grouped before the list got shuffled.
+// Backtracking, with a group abandoned as soon as its members stop sharing anything.
+
+use crate::item_set::ItemSet;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    // indices into the rucksack list, in order
+    pub members: Vec<usize>,
+    pub badge: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Discovery {
+    Impossible,
+    Unique(Vec<Group>),
+    // the first two partitions found, there may be more
+    Ambiguous(Vec<Group>, Vec<Group>),
+}
+
+pub fn discover(rucksacks: &[ItemSet], group_size: usize) -> Discovery {
+    assert!(group_size >= 1, "groups need at least one rucksack");
+    if !rucksacks.len().is_multiple_of(group_size) {
+        return Discovery::Impossible;
+    }
+
+    let mut search = Search { rucksacks, group_size, used: vec![false; rucksacks.len()], groups: vec![], found: vec![] };
+    search.next_group();
+
+    let mut found = search.found.into_iter();
+    match (found.next(), found.next()) {
+        (None, _) => Discovery::Impossible,
+        (Some(groups), None) => Discovery::Unique(groups),
+        (Some(first), Some(second)) => Discovery::Ambiguous(first, second),
+    }
+}
+
+struct Search<'a> {
+    rucksacks: &'a [ItemSet],
+    group_size: usize,
+    used: Vec<bool>,
+    groups: Vec<Group>,
+    // two is enough to know it isn't unique
+    found: Vec<Vec<Group>>,
+}
+
+impl Search<'_> {
+    // the first rucksack nobody has claimed has to go somewhere, so every group starts with it.
+    // that way each partition is only found once rather than once per ordering of its groups.
+    fn next_group(&mut self) {
+        match self.used.iter().position(|&used| !used) {
+            None => self.found.push(self.groups.clone()),
+            Some(first) => {
+                self.used[first] = true;
+                self.fill_group(vec![first], self.rucksacks[first].clone());
+                self.used[first] = false;
+            }
+        }
+    }
+
+    fn fill_group(&mut self, mut members: Vec<usize>, common: ItemSet) {
+        if members.len() == self.group_size {
+            if common.len() == 1 {
+                let badge = common.priorities().next().unwrap();
+                self.groups.push(Group { members, badge });
+                self.next_group();
+                self.groups.pop();
+            }
+            return;
+        }
+
+        // members are picked in increasing order, again so each group only turns up once
+        for next in members[members.len() - 1] + 1..self.rucksacks.len() {
+            if self.found.len() >= 2 {
+                return;
+            }
+            if self.used[next] {
+                continue;
+            }
+            let narrowed = common.intersection(&self.rucksacks[next]);
+            if narrowed.is_empty() {
+                continue;
+            }
+
+            self.used[next] = true;
+            members.push(next);
+            self.fill_group(members.clone(), narrowed);
+            members.pop();
+            self.used[next] = false;
+        }
+    }
+}
+
+#[test]
+fn test_discover() {
+    use crate::priority::PriorityScheme;
+
+    let parse = |lines: &[&str]| -> Vec<_> {
+        lines.iter().map(|line| ItemSet::parse(line, &PriorityScheme::Default).unwrap()).collect()
+    };
+
+    // the example can be grouped two ways: as given, and with the 1st, 5th and 6th sharing J
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let lines: Vec<_> = input.lines().collect();
+    assert_eq!(discover(&parse(&lines), 3), Discovery::Ambiguous(
+        vec![Group { members: vec![0, 1, 2], badge: 18 }, Group { members: vec![3, 4, 5], badge: 52 }],
+        vec![Group { members: vec![0, 4, 5], badge: 36 }, Group { members: vec![1, 2, 3], badge: 17 }],
+    ));
+    assert_eq!(discover(&parse(&lines), 2), Discovery::Impossible);
+    assert_eq!(discover(&parse(&lines), 4), Discovery::Impossible);
+
+    assert_eq!(discover(&parse(&["xy", "ab", "xz", "ac"]), 2), Discovery::Unique(vec![
+        Group { members: vec![0, 2], badge: 24 },
+        Group { members: vec![1, 3], badge: 1 },
+    ]));
+    assert!(matches!(discover(&parse(&["ab", "ab", "ac", "ac"]), 2), Discovery::Ambiguous(..)));
+}
@@ -0,0 +1,78 @@
+// Each compartment as a set of priorities. The first 64 priorities, which covers the puzzle's 52
+// items, live in a u64 where bit n means priority n + 1, so finding what compartments have in
+// common is just `&`. Schemes with bigger priorities keep the rest in a sorted list.
+
+use std::fmt;
+
+use crate::intersect_sorted;
+use crate::priority::PriorityScheme;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet {
+    low: u64,
+    high: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidItem(pub char);
+
+impl fmt::Display for InvalidItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid item `{}`", self.0.escape_debug())
+    }
+}
+
+impl ItemSet {
+    pub fn parse(items: &str, scheme: &PriorityScheme) -> Result<Self, InvalidItem> {
+        let mut set = ItemSet::default();
+        for item in items.chars() {
+            match scheme.priority(item).ok_or(InvalidItem(item))? {
+                priority @ 1..=64 => set.low |= 1 << (priority - 1),
+                priority => set.high.push(priority),
+            }
+        }
+        set.high.sort_unstable();
+        set.high.dedup();
+        Ok(set)
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        ItemSet { low: self.low & other.low, high: intersect_sorted(&self.high, &other.high) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.low.count_ones() as usize + self.high.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.low == 0 && self.high.is_empty()
+    }
+
+    // lowest first
+    pub fn priorities(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..64).filter(|bit| self.low & (1 << bit) != 0).map(|bit| bit + 1).chain(self.high.iter().copied())
+    }
+
+    pub fn items<'a>(&'a self, scheme: &'a PriorityScheme) -> impl Iterator<Item = char> + 'a {
+        self.priorities().filter_map(|priority| scheme.item(priority))
+    }
+
+    pub fn priority_sum(&self) -> usize {
+        self.priorities().sum()
+    }
+}
+
+#[test]
+fn test_item_set() {
+    let scheme = PriorityScheme::Default;
+    let left = ItemSet::parse("vJrwpWtwJgWr", &scheme).unwrap();
+    let right = ItemSet::parse("hcsFMMfFFhFp", &scheme).unwrap();
+    assert_eq!(left.intersection(&right).items(&scheme).collect::<String>(), "p");
+    assert_eq!(ItemSet::parse("aZzA", &scheme).unwrap().items(&scheme).collect::<String>(), "azAZ");
+    assert_eq!(ItemSet::parse("ab1", &scheme), Err(InvalidItem('1')));
+
+    let unicode = PriorityScheme::UnicodeLetters;
+    let left = ItemSet::parse("aéßΩ", &unicode).unwrap();
+    let right = ItemSet::parse("ßaΩx", &unicode).unwrap();
+    assert_eq!(left.intersection(&right).items(&unicode).collect::<String>(), "aßΩ");
+}
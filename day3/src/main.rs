@@ -1,107 +1,352 @@
-use std::collections::HashSet;
-use std::env;
+mod badges;
+mod item_set;
+mod priority;
+mod rebalance;
+mod report;
 
-use itertools::Itertools;
+use std::{env, process};
+use std::time::Instant;
+
+use crate::badges::{Discovery, Group};
+use crate::item_set::{InvalidItem, ItemSet};
+use crate::priority::PriorityScheme;
+use crate::report::Section;
+
+// how the rucksacks are divided up: each one into `compartments` equal parts, and consecutive
+// ones into groups of `group_size`. the items of a group are the ones found in every part of
+// every rucksack in it.
+#[derive(Clone, Copy)]
+struct Grouping {
+    compartments: usize,
+    group_size: usize,
+}
+
+const PART_A: Grouping = Grouping { compartments: 2, group_size: 1 };
+const PART_B: Grouping = Grouping { compartments: 1, group_size: 3 };
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let filename = &args[1];
 
+    // --scheme=default|digits|unicode|<table file>, anywhere on the command line
+    let scheme = args.iter().find_map(|arg| arg.strip_prefix("--scheme=")).unwrap_or("default");
+    let scheme = PriorityScheme::from_arg(scheme).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    let csv = args.iter().any(|arg| arg == "--csv");
+    let args: Vec<_> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    let filename = args[1];
+
+    let result = match args.get(2).map(|arg| arg.as_str()) {
+        // day3 <file> bench [rounds]
+        Some("bench") => {
+            // the timings are averaged over the rounds, so there has to be at least one
+            match args.get(3).map_or(Ok(1000), |rounds| rounds.parse()) {
+                Ok(rounds) if rounds >= 1 => {
+                    let input = std::fs::read_to_string(filename).unwrap();
+                    bench(&input, rounds, &scheme).map_err(|err| err.to_string())
+                }
+                _ => Err(format!("expected a number of rounds of at least 1, got `{}`", args[3])),
+            }
+        }
+        // day3 <file> badges [group size]
+        Some("badges") => {
+            let group_size = args.get(3).map_or(3, |size| size.parse().unwrap());
+            let input = std::fs::read_to_string(filename).unwrap();
+            print_badges(&input, group_size, &scheme).map_err(|err| err.to_string())
+        }
+        // day3 <file> rebalance
+        Some("rebalance") => {
+            let input = std::fs::read_to_string(filename).unwrap();
+            print_rebalance(&input, &scheme).map_err(|err| err.to_string())
+        }
+        // day3 <file> report [items|groups] [--csv], both tables if neither is picked, except for csv
+        Some("report") => {
+            let sections = match args.get(3).map(|arg| arg.as_str()) {
+                Some("items") => vec![Section::Items],
+                Some("groups") => vec![Section::Groups],
+                Some(other) => panic!("Unknown report section {}", other),
+                None if csv => vec![Section::Items],
+                None => vec![Section::Items, Section::Groups],
+            };
+            let input = std::fs::read_to_string(filename).unwrap();
+            report::report(&input, &scheme).map(|report| {
+                let tables: Vec<_> = sections.into_iter().map(|section| {
+                    if csv { report.to_csv(section) } else { report.to_table(section) }
+                }).collect();
+                print!("{}", tables.join("\n"));
+            }).map_err(|err| err.to_string())
+        }
+        // day3 <file> <compartments> <group size>
+        Some(compartments) => parse_grouping(compartments, args.get(3).map(|arg| arg.as_str())).and_then(|grouping| {
+            let input = std::fs::read_to_string(filename).unwrap();
+            print_groups(&input, grouping, &scheme).map_err(|err| err.to_string())
+        }),
+        None => print_parts(filename, &scheme).map_err(|err| err.to_string()),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+// anything that isn't a mode has to be a grouping, so a mistyped mode ends up here too
+fn parse_grouping(compartments: &str, group_size: Option<&str>) -> Result<Grouping, String> {
+    let at_least_one = |arg: Option<&str>, what: &str| match arg {
+        None => Err(format!("expected {} of at least 1", what)),
+        Some(arg) => match arg.parse() {
+            Ok(n) if n >= 1 => Ok(n),
+            _ => Err(format!("expected {} of at least 1, got `{}`", what, arg)),
+        },
+    };
+    Ok(Grouping {
+        compartments: at_least_one(Some(compartments), "a mode or a number of compartments")?,
+        group_size: at_least_one(group_size, "a group size")?,
+    })
+}
+
+fn print_parts(filename: &str, scheme: &PriorityScheme) -> Result<(), InvalidItem> {
     let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", a(input));
+    println!("{}", a(input, scheme)?);
 
     let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", b(input));
+    println!("{}", b(input, scheme)?);
+    Ok(())
 }
 
-fn find_common_item(left_compartment: &str, right_compartment: &str) -> char {
-    // just wanted to try an overengineered solution for this one
-    let mut left_compartment: Vec<_> = left_compartment.chars().collect();
-    let mut right_compartment: Vec<_> = right_compartment.chars().collect();
-    left_compartment.sort();
-    left_compartment.dedup();
-    right_compartment.sort();
-    right_compartment.dedup();
+fn print_groups(input: &str, grouping: Grouping, scheme: &PriorityScheme) -> Result<(), InvalidItem> {
+    let lines = rucksacks(input);
+    for group in lines.chunks(grouping.group_size) {
+        let items: String = common_item_set(group, grouping.compartments, scheme)?.items(scheme).collect();
+        println!("{}: {}", group.join(" "), items);
+    }
+    println!("{}", score(input, grouping, scheme)?);
+    Ok(())
+}
+
+// lines are numbered from 1 here, since the whole point is they aren't in order
+fn print_badges(input: &str, group_size: usize, scheme: &PriorityScheme) -> Result<(), InvalidItem> {
+    let rucksacks = rucksacks(input).into_iter().map(|line| ItemSet::parse(line, scheme)).collect::<Result<Vec<_>, _>>()?;
+    let print_partition = |groups: &[Group]| {
+        for group in groups {
+            let members: Vec<_> = group.members.iter().map(|member| (member + 1).to_string()).collect();
+            let badge = scheme.item(group.badge).unwrap();
+            println!("{}: {}", members.join(" "), badge);
+        }
+        println!("{}", groups.iter().map(|group| group.badge).sum::<usize>());
+    };
+
+    match badges::discover(&rucksacks, group_size) {
+        Discovery::Impossible => println!("no way to split into groups of {} with one badge each", group_size),
+        Discovery::Unique(groups) => {
+            print_partition(&groups);
+            println!("unique");
+        }
+        Discovery::Ambiguous(first, second) => {
+            print_partition(&first);
+            println!("not unique, another one is");
+            print_partition(&second);
+        }
+    }
+    Ok(())
+}
 
+fn print_rebalance(input: &str, scheme: &PriorityScheme) -> Result<(), InvalidItem> {
+    let mut moves = 0;
+    let mut cost = 0;
+    for rucksack in rucksacks(input) {
+        let fixed = rebalance::rebalance(rucksack, scheme)?;
+        println!("{} {}: {} moved, cost {}", fixed.left, fixed.right, fixed.moves, fixed.cost);
+        moves += fixed.moves;
+        cost += fixed.cost;
+    }
+    println!("{} moved, cost {}", moves, cost);
+    Ok(())
+}
+
+// the sorted vectors against the bitmasks, on both parts. the bitmasks go first so a bad item
+// comes back as an error rather than a panic from the sorted version.
+fn bench(input: &str, rounds: usize, scheme: &PriorityScheme) -> Result<(), InvalidItem> {
+    for (name, grouping) in [("a", PART_A), ("b", PART_B)] {
+        let start = Instant::now();
+        let mut bitmask = 0;
+        for _ in 0..rounds {
+            bitmask = score(input, grouping, scheme)?;
+        }
+        let bitmask_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut sorted = 0;
+        for _ in 0..rounds {
+            sorted = score_sorted(input, grouping, scheme);
+        }
+        let sorted_time = start.elapsed();
+
+        assert_eq!(sorted, bitmask);
+        println!("{}: {} sorted {:?}, bitmask {:?}", name, bitmask, sorted_time / rounds as u32, bitmask_time / rounds as u32);
+    }
+    Ok(())
+}
+
+// blank lines, like the one after a trailing newline, aren't rucksacks
+fn rucksacks(input: &str) -> Vec<&str> {
+    input.lines().filter(|line| !line.trim().is_empty()).collect()
+}
+
+fn split_compartments(rucksack: &str, compartments: usize) -> Vec<&str> {
+    // byte offsets of each item, plus the end, so non-ascii items don't get cut in half
+    let offsets: Vec<_> = rucksack.char_indices().map(|(offset, _)| offset).chain([rucksack.len()]).collect();
+    let items = offsets.len() - 1;
+    assert!(items % compartments == 0, "Rucksack doesn't split into {} equal compartments", compartments);
+
+    let size = items / compartments;
+    (0..compartments).map(|n| &rucksack[offsets[n * size]..offsets[(n + 1) * size]]).collect()
+}
+
+fn intersect_sorted<T: Copy + Ord>(left: &[T], right: &[T]) -> Vec<T> {
+    let mut common = vec![];
     let mut left_ptr = 0;
     let mut right_ptr = 0;
-    while left_ptr < left_compartment.len() && right_ptr < right_compartment.len() {
-        let left_char = left_compartment[left_ptr];
-        let right_char = right_compartment[right_ptr];
+    while left_ptr < left.len() && right_ptr < right.len() {
+        let left_char = left[left_ptr];
+        let right_char = right[right_ptr];
 
         if left_char == right_char {
-            break;
+            common.push(left_char);
+            left_ptr += 1;
+            right_ptr += 1;
         } else if left_char < right_char {
             left_ptr += 1;
-        } else if left_char > right_char {
+        } else {
             right_ptr += 1;
         }
     }
 
-    left_compartment[left_ptr]
+    common
 }
 
-fn get_badge<'a, I>(chunk: &mut I) -> char where I: Iterator<Item = &'a str> {
-    let mut candidates: HashSet<_> = HashSet::from_iter(chunk.next().unwrap().chars());
+// every item type found in all of the parts, in order. empty if there isn't one.
+fn find_common_items(parts: &[&str]) -> Vec<char> {
+    // just wanted to try an overengineered solution for this one
+    let sorted_parts = parts.iter().map(|part| {
+        let mut items: Vec<_> = part.chars().collect();
+        items.sort();
+        items.dedup();
+        items
+    });
 
-    // can use reduce -> intersection instead
-    for line in chunk {
-        let ruled_out: HashSet<_> = HashSet::from_iter(line.chars());
-        candidates = candidates.intersection(&ruled_out).copied().collect();
-    }
+    sorted_parts.reduce(|common, items| intersect_sorted(&common, &items)).unwrap_or_default()
+}
 
-    candidates.into_iter().next().unwrap()
+fn common_items(group: &[&str], compartments: usize) -> Vec<char> {
+    let parts: Vec<_> = group.iter().flat_map(|rucksack| split_compartments(rucksack, compartments)).collect();
+    find_common_items(&parts)
 }
 
-fn get_priority(item: char) -> usize {
-    if 'a' <= item && item <= 'z' {
-        return 1 + item as usize - 'a' as usize;
-    } else if 'A' <= item && item <= 'Z' {
-        return 27 + item as usize - 'A' as usize;
-    } else {
-        panic!("Invalid item code");
-    }
+fn common_item_set(group: &[&str], compartments: usize, scheme: &PriorityScheme) -> Result<ItemSet, InvalidItem> {
+    let common = group.iter()
+        .flat_map(|rucksack| split_compartments(rucksack, compartments))
+        .try_fold(None, |common: Option<ItemSet>, part| {
+            let set = ItemSet::parse(part, scheme)?;
+            Ok(Some(match common {
+                Some(common) => common.intersection(&set),
+                None => set,
+            }))
+        })?;
+    Ok(common.unwrap_or_default())
+}
+
+fn get_priority(item: char, scheme: &PriorityScheme) -> usize {
+    scheme.priority(item).expect("Invalid item code")
+}
+
+// the original implementation, kept around to benchmark against
+fn score_sorted(input: &str, grouping: Grouping, scheme: &PriorityScheme) -> usize {
+    let lines = rucksacks(input);
+    lines.chunks(grouping.group_size)
+        .flat_map(|group| common_items(group, grouping.compartments))
+        .map(|item| get_priority(item, scheme))
+        .sum()
+}
+
+fn score(input: &str, grouping: Grouping, scheme: &PriorityScheme) -> Result<usize, InvalidItem> {
+    let lines = rucksacks(input);
+    lines.chunks(grouping.group_size)
+        .map(|group| Ok(common_item_set(group, grouping.compartments, scheme)?.priority_sum()))
+        .sum()
 }
 
-fn a(input: String) -> usize {
-    input.split("\n").map(|line| {
-        let halfway_point = line.len() / 2;
-        // can use split_at instead
-        let (first_half, second_half) = (&line[..halfway_point], &line[halfway_point..]);
-        get_priority(find_common_item(first_half, second_half))
-    }).sum()
+fn a(input: String, scheme: &PriorityScheme) -> Result<usize, InvalidItem> {
+    score(&input, PART_A, scheme)
 }
 
-fn b(input: String) -> usize {
-    input.split("\n").chunks(3).into_iter().map(|mut group| get_priority(get_badge(&mut group))).sum()
+fn b(input: String, scheme: &PriorityScheme) -> Result<usize, InvalidItem> {
+    score(&input, PART_B, scheme)
 }
 
 #[test]
 fn test_get_priority_lowercase() {
-    assert_eq!(get_priority('a'), 1);
-    assert_eq!(get_priority('p'), 16);
-    assert_eq!(get_priority('s'), 19);
-    assert_eq!(get_priority('t'), 20);
-    assert_eq!(get_priority('v'), 22);
-    assert_eq!(get_priority('z'), 26);
+    assert_eq!(get_priority('a', &PriorityScheme::Default), 1);
+    assert_eq!(get_priority('p', &PriorityScheme::Default), 16);
+    assert_eq!(get_priority('s', &PriorityScheme::Default), 19);
+    assert_eq!(get_priority('t', &PriorityScheme::Default), 20);
+    assert_eq!(get_priority('v', &PriorityScheme::Default), 22);
+    assert_eq!(get_priority('z', &PriorityScheme::Default), 26);
 }
 
 #[test]
 fn test_get_priority_uppercase() {
-    assert_eq!(get_priority('A'), 27);
-    assert_eq!(get_priority('L'), 38);
-    assert_eq!(get_priority('P'), 42);
-    assert_eq!(get_priority('Z'), 52);
+    assert_eq!(get_priority('A', &PriorityScheme::Default), 27);
+    assert_eq!(get_priority('L', &PriorityScheme::Default), 38);
+    assert_eq!(get_priority('P', &PriorityScheme::Default), 42);
+    assert_eq!(get_priority('Z', &PriorityScheme::Default), 52);
 }
 
 #[test]
 fn test_example_a() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
-    assert_eq!(a(input), 157);
+    assert_eq!(a(input, &PriorityScheme::Default), Ok(157));
 }
 
 #[test]
 fn test_example_b() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
-    assert_eq!(b(input), 12);
-}
\ No newline at end of file
+    assert_eq!(b(input, &PriorityScheme::Default), Ok(70));
+}
+
+#[test]
+fn test_common_items() {
+    // nothing in common, more than one thing in common, and three compartments
+    assert_eq!(common_items(&["abcDEF"], 2), []);
+    assert_eq!(common_items(&["abcabd"], 2), ['a', 'b']);
+    assert_eq!(common_items(&["xaYaaZ"], 3), ['a']);
+    assert_eq!(common_items(&["abXY", "XYcd"], 1), ['X', 'Y']);
+    assert_eq!(common_items(&["abXa", "XYca"], 2), []);
+}
+#[test]
+fn test_parse_grouping() {
+    let grouping = parse_grouping("2", Some("3")).unwrap();
+    assert_eq!((grouping.compartments, grouping.group_size), (2, 3));
+    assert_eq!(parse_grouping("typo", None).err().unwrap(), "expected a mode or a number of compartments of at least 1, got `typo`");
+    assert!(parse_grouping("0", Some("3")).is_err());
+    assert!(parse_grouping("2", Some("0")).is_err());
+    assert!(parse_grouping("2", None).is_err());
+}
+
+#[test]
+fn test_engines_agree() {
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    for grouping in [PART_A, PART_B, Grouping { compartments: 1, group_size: 2 }] {
+        assert_eq!(score(&input, grouping, &PriorityScheme::Default), Ok(score_sorted(&input, grouping, &PriorityScheme::Default)));
+    }
+    assert_eq!(a("abcA\nab-b".to_string(), &PriorityScheme::Default), Err(InvalidItem('-')));
+    assert_eq!(a("1231\n4554".to_string(), &PriorityScheme::Digits), Ok(2 + 5 + 6));
+
+    // a trailing newline and a blank line in the middle don't make or shift any groups
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let spaced = input.replacen('\n', "\n\n", 1) + "\n";
+    assert_eq!(b(spaced.clone(), &PriorityScheme::Default), Ok(70));
+    assert_eq!(score_sorted(&spaced, PART_B, &PriorityScheme::Default), 70);
+    assert_eq!(score_sorted("1231\n4554", PART_A, &PriorityScheme::Digits), 2 + 5 + 6);
+}
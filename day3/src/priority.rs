@@ -0,0 +1,129 @@
+// Which items there are and what each one is worth. The puzzle's scheme is the default, but the
+// rest of day3 doesn't care where priorities come from as long as every item has its own.
+
+use std::collections::HashMap;
+
+pub enum PriorityScheme {
+    // a-z are 1-26, A-Z 27-52
+    Default,
+    // 0-9 are 1-10
+    Digits,
+    // ascii letters as in the default, then every other letter after them in code point order
+    UnicodeLetters,
+    // read from a file of `<item> <priority>` lines
+    Table(HashMap<char, usize>),
+}
+
+// anything that isn't an ascii letter is at least this far past the code point, so it can't
+// clash with the default range
+const UNICODE_OFFSET: usize = 52;
+
+impl PriorityScheme {
+    pub fn from_arg(arg: &str) -> Result<Self, String> {
+        match arg {
+            "default" => Ok(PriorityScheme::Default),
+            "digits" => Ok(PriorityScheme::Digits),
+            "unicode" => Ok(PriorityScheme::UnicodeLetters),
+            path => {
+                let table = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+                Self::from_table(&table).map_err(|err| format!("{}: {}", path, err))
+            }
+        }
+    }
+
+    pub fn from_table(table: &str) -> Result<Self, String> {
+        let mut priorities = HashMap::new();
+        let mut seen = HashMap::new();
+
+        for (line, code) in table.lines().enumerate() {
+            let code = code.trim();
+            if code.is_empty() || code.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = code.split_whitespace();
+            let (item, priority) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(item), Some(priority), None) => (item, priority),
+                _ => return Err(format!("line {}: expected `<item> <priority>`", line + 1)),
+            };
+            let mut chars = item.chars();
+            let item = match (chars.next(), chars.next()) {
+                (Some(item), None) => item,
+                _ => return Err(format!("line {}: `{}` isn't a single item", line + 1, item)),
+            };
+            let priority: usize = match priority.parse() {
+                Ok(priority) if priority > 0 => priority,
+                _ => return Err(format!("line {}: `{}` isn't a positive priority", line + 1, priority)),
+            };
+
+            // two items sharing a priority would be the same item as far as the bitmasks go
+            if let Some(other) = seen.insert(priority, item) {
+                return Err(format!("line {}: `{}` and `{}` both have priority {}", line + 1, other, item, priority));
+            }
+            if priorities.insert(item, priority).is_some() {
+                return Err(format!("line {}: `{}` is listed twice", line + 1, item));
+            }
+        }
+
+        Ok(PriorityScheme::Table(priorities))
+    }
+
+    pub fn priority(&self, item: char) -> Option<usize> {
+        match self {
+            PriorityScheme::Default => ascii_letter_priority(item),
+            PriorityScheme::Digits => item.to_digit(10).map(|digit| 1 + digit as usize),
+            PriorityScheme::UnicodeLetters => ascii_letter_priority(item).or_else(|| {
+                (item.is_alphabetic() && !item.is_ascii()).then(|| UNICODE_OFFSET + item as usize)
+            }),
+            PriorityScheme::Table(priorities) => priorities.get(&item).copied(),
+        }
+    }
+
+    // the other way round, for showing which items were found
+    pub fn item(&self, priority: usize) -> Option<char> {
+        match self {
+            PriorityScheme::Default => ascii_letter_item(priority),
+            PriorityScheme::Digits => char::from_digit(priority.checked_sub(1)? as u32, 10),
+            PriorityScheme::UnicodeLetters => ascii_letter_item(priority).or_else(|| {
+                char::from_u32(priority.checked_sub(UNICODE_OFFSET)? as u32).filter(|item| item.is_alphabetic() && !item.is_ascii())
+            }),
+            PriorityScheme::Table(priorities) => {
+                priorities.iter().find(|&(_, &p)| p == priority).map(|(&item, _)| item)
+            }
+        }
+    }
+}
+
+fn ascii_letter_priority(item: char) -> Option<usize> {
+    if item.is_ascii_lowercase() {
+        Some(1 + item as usize - 'a' as usize)
+    } else if item.is_ascii_uppercase() {
+        Some(27 + item as usize - 'A' as usize)
+    } else {
+        None
+    }
+}
+
+fn ascii_letter_item(priority: usize) -> Option<char> {
+    match priority {
+        1..=26 => Some((b'a' + (priority - 1) as u8) as char),
+        27..=52 => Some((b'A' + (priority - 27) as u8) as char),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_schemes() {
+    assert_eq!(PriorityScheme::Default.priority('é'), None);
+    assert_eq!(PriorityScheme::Digits.priority('0'), Some(1));
+    assert_eq!(PriorityScheme::Digits.priority('a'), None);
+    assert_eq!(PriorityScheme::UnicodeLetters.priority('Z'), Some(52));
+
+    let unicode = PriorityScheme::UnicodeLetters.priority('é').unwrap();
+    assert_eq!(PriorityScheme::UnicodeLetters.item(unicode), Some('é'));
+
+    let table = PriorityScheme::from_table("# vowels\na 5\ne 4\n").unwrap();
+    assert_eq!(table.priority('e'), Some(4));
+    assert_eq!(table.item(5), Some('a'));
+    assert!(PriorityScheme::from_table("a 1\nb 1\n").is_err());
+}
@@ -0,0 +1,54 @@
+// Putting the misplaced items back. Every item type found in both compartments gets gathered
+// into whichever one already holds more of it, which is the fewest items that can be moved.
+// Ties go to the first compartment. The compartments can end up different sizes.
+
+use crate::item_set::InvalidItem;
+use crate::priority::PriorityScheme;
+use crate::{find_common_items, split_compartments};
+
+#[derive(Debug, PartialEq)]
+pub struct Rebalance {
+    pub left: String,
+    pub right: String,
+    pub moves: usize,
+    // the priority of every item moved, added up
+    pub cost: usize,
+}
+
+pub fn rebalance(rucksack: &str, scheme: &PriorityScheme) -> Result<Rebalance, InvalidItem> {
+    let halves = split_compartments(rucksack, 2);
+    let (mut left, mut right) = (halves[0].to_string(), halves[1].to_string());
+    let mut moves = 0;
+    let mut cost = 0;
+
+    for item in find_common_items(&halves) {
+        let priority = scheme.priority(item).ok_or(InvalidItem(item))?;
+        let in_left = left.chars().filter(|&c| c == item).count();
+        let in_right = right.chars().filter(|&c| c == item).count();
+
+        // keep the original order of whatever stays put and add the moved items to the end
+        let (from, to) = if in_left >= in_right { (&mut right, &mut left) } else { (&mut left, &mut right) };
+        let moved = in_left.min(in_right);
+        from.retain(|c| c != item);
+        to.extend(std::iter::repeat_n(item, moved));
+
+        moves += moved;
+        cost += moved * priority;
+    }
+
+    Ok(Rebalance { left, right, moves, cost })
+}
+
+#[test]
+fn test_rebalance() {
+    let scheme = PriorityScheme::Default;
+    let fixed = rebalance("vJrwpWtwJgWrhcsFMMfFFhFp", &scheme).unwrap();
+    assert_eq!(fixed, Rebalance { left: "vJrwpWtwJgWrp".to_string(), right: "hcsFMMfFFhF".to_string(), moves: 1, cost: 16 });
+
+    // the a's go right and the b's go left
+    let fixed = rebalance("abbcaabd", &scheme).unwrap();
+    assert_eq!(fixed, Rebalance { left: "bbcb".to_string(), right: "aada".to_string(), moves: 2, cost: 3 });
+    // a tie stays on the left
+    assert_eq!(rebalance("xaay", &scheme).unwrap().left, "xaa");
+    assert_eq!(rebalance("ab", &scheme).unwrap().moves, 0);
+}
@@ -0,0 +1,168 @@
+// Where the answers come from: for every item type, how often it was the misplaced one in a
+// rucksack and how often it was a group's badge, and the same broken down group by group.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::item_set::{InvalidItem, ItemSet};
+use crate::priority::PriorityScheme;
+use crate::{common_item_set, rucksacks, PART_A, PART_B};
+
+#[derive(Debug, PartialEq)]
+pub struct ItemStats {
+    pub item: char,
+    pub priority: usize,
+    pub misplaced: usize,
+    pub badge: usize,
+}
+
+impl ItemStats {
+    // what this item added to both answers together
+    pub fn total(&self) -> usize {
+        (self.misplaced + self.badge) * self.priority
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GroupStats {
+    // line numbers, counted from 1
+    pub first_line: usize,
+    pub last_line: usize,
+    // one entry per rucksack
+    pub misplaced: Vec<String>,
+    pub badge: String,
+    pub total: usize,
+}
+
+pub struct Report {
+    // in priority order, only the items that turned up
+    pub items: Vec<ItemStats>,
+    pub groups: Vec<GroupStats>,
+}
+
+pub fn report(input: &str, scheme: &PriorityScheme) -> Result<Report, InvalidItem> {
+    let lines = rucksacks(input);
+    // priority -> (misplaced, badge)
+    let mut counts: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    let mut groups = vec![];
+
+    for (n, group) in lines.chunks(PART_B.group_size).enumerate() {
+        let mut misplaced = vec![];
+        let mut total = 0;
+        for &rucksack in group {
+            let common = common_item_set(&[rucksack], PART_A.compartments, scheme)?;
+            for priority in common.priorities() {
+                counts.entry(priority).or_default().0 += 1;
+            }
+            misplaced.push(describe(&common, scheme));
+            total += common.priority_sum();
+        }
+
+        let badge = common_item_set(group, PART_B.compartments, scheme)?;
+        for priority in badge.priorities() {
+            counts.entry(priority).or_default().1 += 1;
+        }
+        total += badge.priority_sum();
+
+        let first_line = n * PART_B.group_size + 1;
+        groups.push(GroupStats { first_line, last_line: first_line + group.len() - 1, misplaced, badge: describe(&badge, scheme), total });
+    }
+
+    let items = counts.into_iter().map(|(priority, (misplaced, badge))| {
+        ItemStats { item: scheme.item(priority).unwrap(), priority, misplaced, badge }
+    }).collect();
+
+    Ok(Report { items, groups })
+}
+
+fn describe(items: &ItemSet, scheme: &PriorityScheme) -> String {
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.items(scheme).collect()
+    }
+}
+
+// the report is two tables, and a CSV file can only hold one of them
+#[derive(Clone, Copy, PartialEq)]
+pub enum Section {
+    Items,
+    Groups,
+}
+
+impl Report {
+    pub fn to_table(&self, section: Section) -> String {
+        let mut table = String::new();
+        match section {
+            Section::Items => {
+                writeln!(table, "item priority misplaced badge total").unwrap();
+                for stats in &self.items {
+                    writeln!(table, "{:>4} {:>8} {:>9} {:>5} {:>5}", stats.item, stats.priority, stats.misplaced, stats.badge, stats.total()).unwrap();
+                }
+            }
+            Section::Groups => {
+                // padding counts chars, so the width has to as well
+                let width = self.groups.iter().map(|group| group.misplaced.join(" ").chars().count()).max().unwrap_or(0).max(9);
+                writeln!(table, "lines   {:<width$} badge total", "misplaced").unwrap();
+                for group in &self.groups {
+                    let lines = format!("{}-{}", group.first_line, group.last_line);
+                    writeln!(table, "{:<7} {:<width$} {:>5} {:>5}", lines, group.misplaced.join(" "), group.badge, group.total).unwrap();
+                }
+            }
+        }
+        table
+    }
+
+    pub fn to_csv(&self, section: Section) -> String {
+        let mut csv = String::new();
+        match section {
+            Section::Items => {
+                writeln!(csv, "item,priority,misplaced,badge,total").unwrap();
+                for stats in &self.items {
+                    writeln!(csv, "{},{},{},{},{}", csv_field(&stats.item.to_string()), stats.priority, stats.misplaced, stats.badge, stats.total()).unwrap();
+                }
+            }
+            Section::Groups => {
+                writeln!(csv, "first_line,last_line,misplaced,badge,total").unwrap();
+                for group in &self.groups {
+                    writeln!(csv, "{},{},{},{},{}", group.first_line, group.last_line, csv_field(&group.misplaced.join(" ")), csv_field(&group.badge), group.total).unwrap();
+                }
+            }
+        }
+        csv
+    }
+}
+
+// items come from whatever scheme is in use, so a comma or quote could be one of them
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[test]
+fn test_report() {
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let report = report(&input, &PriorityScheme::Default).unwrap();
+
+    // the answers to both parts are in there
+    assert_eq!(report.items.iter().map(|stats| stats.misplaced * stats.priority).sum::<usize>(), 157);
+    assert_eq!(report.items.iter().map(|stats| stats.badge * stats.priority).sum::<usize>(), 70);
+    assert_eq!(report.items[0], ItemStats { item: 'p', priority: 16, misplaced: 1, badge: 0 });
+    assert_eq!(report.groups[0], GroupStats {
+        first_line: 1,
+        last_line: 3,
+        misplaced: vec!["p".to_string(), "L".to_string(), "P".to_string()],
+        badge: "r".to_string(),
+        total: 16 + 38 + 42 + 18,
+    });
+
+    assert!(report.to_csv(Section::Items).starts_with("item,priority,misplaced,badge,total\np,16,1,0,16\n"));
+    assert_eq!(report.to_csv(Section::Groups), "first_line,last_line,misplaced,badge,total\n1,3,p L P,r,114\n4,6,v t s,Z,113\n");
+
+    // the misplaced column is as wide as "èéê èéê èéê" in chars, not bytes
+    let report = crate::report::report("aéèêbéèê\naéèêbéèê\naéèêbéèê", &PriorityScheme::UnicodeLetters).unwrap();
+    assert_eq!(report.to_table(Section::Groups), "lines   misplaced   badge total\n1-3     èéê èéê èéê abèéê  3423\n");
+}
@@ -0,0 +1,149 @@
+// Drawing the assignments like the puzzle statement does, one row per shift:
+//
+//     .234.....  2-4
+//     .....678.  6-8
+//
+// sections another shift in the same group also works are drawn as `#`. with big section
+// numbers the compressed axis gives each stretch of sections between any two shift boundaries a
+// single column instead.
+
+use std::fmt::Write;
+
+use crate::{Shift, ShiftGroup};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Cell {
+    Unassigned,
+    Assigned,
+    Overlapped,
+}
+
+pub struct Chart {
+    // the sections each column stands for
+    columns: Vec<Shift>,
+    compressed: bool,
+    groups: Vec<Vec<(Shift, Vec<Cell>)>>,
+}
+
+impl Chart {
+    pub fn new<'a>(groups: impl Iterator<Item = &'a ShiftGroup>, compressed: bool) -> Self {
+        let groups: Vec<_> = groups.collect();
+        let shifts = || groups.iter().flat_map(|group| &group.0).filter(|shift| !shift.is_empty());
+        let first = shifts().map(|shift| shift.0).min().unwrap_or(1).min(1);
+        let last = shifts().map(|shift| shift.1).max().unwrap_or(0);
+
+        let columns = if compressed {
+            // every shift starts at a boundary and stops just before one, so a column is either
+            // entirely inside a shift or entirely outside it. a shift running to usize::MAX stops
+            // just before one past it
+            let mut boundaries: Vec<_> = shifts()
+                .flat_map(|shift| [shift.0 as u128, shift.1 as u128 + 1])
+                .chain([first as u128])
+                .collect();
+            boundaries.sort_unstable();
+            boundaries.dedup();
+            boundaries.windows(2).map(|pair| Shift(pair[0] as usize, (pair[1] - 1) as usize)).collect()
+        } else {
+            (first..=last).map(|section| Shift(section, section)).collect::<Vec<_>>()
+        };
+
+        let groups = groups.iter().map(|group| {
+            group.0.iter().enumerate().map(|(n, shift)| {
+                let cells = columns.iter().map(|column| {
+                    let worked_by_other = group.0.iter().enumerate().any(|(m, other)| m != n && other.contains(column));
+                    match (shift.contains(column), worked_by_other) {
+                        (false, _) => Cell::Unassigned,
+                        (true, false) => Cell::Assigned,
+                        (true, true) => Cell::Overlapped,
+                    }
+                }).collect();
+                (*shift, cells)
+            }).collect()
+        }).collect();
+
+        Chart { columns, compressed, groups }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (n, group) in self.groups.iter().enumerate() {
+            if n > 0 {
+                text.push('\n');
+            }
+            for (shift, cells) in group {
+                let row: String = cells.iter().zip(&self.columns).map(|(cell, column)| match cell {
+                    Cell::Unassigned => '.',
+                    Cell::Overlapped => '#',
+                    Cell::Assigned if self.compressed => '=',
+                    Cell::Assigned => char::from_digit((column.0 % 10) as u32, 10).unwrap(),
+                }).collect();
+                writeln!(text, "{}  {}", row, shift).unwrap();
+            }
+        }
+
+        if self.compressed {
+            let columns: Vec<_> = self.columns.iter().map(Shift::to_string).collect();
+            writeln!(text, "\ncolumns: {}", columns.join(" ")).unwrap();
+        }
+        text
+    }
+
+    pub fn to_svg(&self) -> String {
+        const CELL: usize = 12;
+        const LABEL_WIDTH: usize = 80;
+
+        let rows: usize = self.groups.iter().map(Vec::len).sum::<usize>() + self.groups.len().saturating_sub(1);
+        let width = self.columns.len() * CELL + LABEL_WIDTH;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"10\">\n",
+            width, rows * CELL,
+        );
+
+        let mut y = 0;
+        for group in &self.groups {
+            for (shift, cells) in group {
+                for (x, cell) in cells.iter().enumerate() {
+                    let fill = match cell {
+                        Cell::Unassigned => continue,
+                        Cell::Assigned => "steelblue",
+                        Cell::Overlapped => "crimson",
+                    };
+                    writeln!(
+                        svg, "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                        x * CELL, y + 1, CELL, CELL - 2, fill,
+                    ).unwrap();
+                }
+                writeln!(svg, "  <text x=\"{}\" y=\"{}\">{}</text>", self.columns.len() * CELL + 4, y + CELL - 3, shift).unwrap();
+                y += CELL;
+            }
+            // a blank row between groups
+            y += CELL;
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[test]
+fn test_example_chart() {
+    let groups: Vec<ShiftGroup> = ["2-4,6-8", "5-7,7-9"].iter().map(|code| code.parse().unwrap()).collect();
+
+    assert_eq!(Chart::new(groups.iter(), false).to_text(), "\
+.234.....  2-4
+.....678.  6-8
+
+....56#..  5-7
+......#89  7-9
+");
+
+    assert_eq!(Chart::new(groups.iter(), true).to_text(), "\
+.=.....  2-4
+...===.  6-8
+
+..==#..  5-7
+....#==  7-9
+
+columns: 1-1 2-4 5-5 6-6 7-7 8-8 9-9
+");
+}
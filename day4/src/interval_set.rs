@@ -0,0 +1,89 @@
+// A set of sections stored as sorted, non-overlapping shifts. Shifts that overlap or
+// sit right next to each other are merged as they go in, so 2-4 and 5-6 become 2-6.
+
+use std::fmt;
+
+use crate::Shift;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet(Vec<Shift>);
+
+impl IntervalSet {
+    pub fn insert(&mut self, shift: Shift) {
+        if shift.is_empty() {
+            return;
+        }
+
+        // everything touching the new shift gets folded into it
+        let first = self.0.partition_point(|existing| existing.1.saturating_add(1) < shift.0);
+        let last = self.0.partition_point(|existing| existing.0 <= shift.1.saturating_add(1));
+        let merged = self.0[first..last].iter().fold(shift, |merged, existing| {
+            Shift(merged.0.min(existing.0), merged.1.max(existing.1))
+        });
+        self.0.splice(first..last, [merged]);
+    }
+
+    // total number of sections covered
+    pub fn len(&self) -> usize {
+        self.0.iter().map(Shift::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Shift> {
+        self.0.iter()
+    }
+
+    pub fn contains(&self, shift: &Shift) -> bool {
+        shift.is_empty() || self.0.iter().any(|existing| existing.contains(shift))
+    }
+
+    // the sections missing between the first and last covered ones
+    pub fn gaps(&self) -> IntervalSet {
+        IntervalSet(self.0.windows(2).map(|pair| Shift(pair[0].1 + 1, pair[1].0 - 1)).collect())
+    }
+
+    // the sections in `within` that aren't covered
+    pub fn uncovered(&self, within: Shift) -> IntervalSet {
+        self.0.iter().fold(IntervalSet::from_iter([within]), |remaining, covered| {
+            remaining.iter().flat_map(|shift| shift.difference(covered)).collect()
+        })
+    }
+}
+
+impl FromIterator<Shift> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Shift>>(iter: I) -> Self {
+        let mut set = IntervalSet::default();
+        for shift in iter {
+            set.insert(shift);
+        }
+        set
+    }
+}
+
+impl IntoIterator for IntervalSet {
+    type Item = Shift;
+    type IntoIter = std::vec::IntoIter<Shift>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shifts: Vec<_> = self.0.iter().map(Shift::to_string).collect();
+        write!(f, "{}", shifts.join(", "))
+    }
+}
+
+#[test]
+fn test_merging() {
+    let set: IntervalSet = [Shift(6, 8), Shift(2, 4), Shift(12, 15), Shift(5, 5), Shift(14, 20), Shift(9, 3)].into_iter().collect();
+    assert_eq!(set.to_string(), "2-8, 12-20");
+    assert_eq!(set.len(), 16);
+    assert_eq!(set.gaps().to_string(), "9-11");
+    assert_eq!(set.uncovered(Shift(1, 25)).to_string(), "1-1, 9-11, 21-25");
+}
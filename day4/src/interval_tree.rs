@@ -0,0 +1,94 @@
+// A static interval tree over all the shifts in the input. The shifts are sorted by start and the
+// sorted array is read as a balanced binary tree, each node remembering the latest end anywhere
+// beneath it so whole subtrees that finish too early can be skipped. Queries are O(log n) plus
+// the number of shifts found.
+
+use crate::sweep::ShiftId;
+use crate::Shift;
+
+pub struct IntervalTree {
+    shifts: Vec<(ShiftId, Shift)>,
+    // for the subtree rooted at each index, the last section any of its shifts works
+    max_end: Vec<usize>,
+}
+
+impl IntervalTree {
+    pub fn new(shifts: &[(ShiftId, Shift)]) -> Self {
+        let mut shifts: Vec<_> = shifts.iter().copied().filter(|(_, shift)| !shift.is_empty()).collect();
+        shifts.sort_unstable_by_key(|&(id, shift)| (shift.0, id));
+
+        let mut tree = IntervalTree { max_end: vec![0; shifts.len()], shifts };
+        tree.build(0, tree.shifts.len());
+        tree
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = (lo + hi) / 2;
+        let left = self.build(lo, mid);
+        let right = self.build(mid + 1, hi);
+        let (_, shift) = self.shifts[mid];
+        self.max_end[mid] = shift.1.max(left).max(right);
+        self.max_end[mid]
+    }
+
+    pub fn overlapping(&self, range: Shift) -> Vec<(ShiftId, Shift)> {
+        let mut found = vec![];
+        if !range.is_empty() {
+            self.search(0, self.shifts.len(), range, &mut found);
+        }
+        found
+    }
+
+    pub fn containing(&self, section: usize) -> Vec<(ShiftId, Shift)> {
+        self.overlapping(Shift(section, section))
+    }
+
+    fn search(&self, lo: usize, hi: usize, range: Shift, found: &mut Vec<(ShiftId, Shift)>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        if self.max_end[mid] < range.0 {
+            // everything down here is over before the range starts
+            return;
+        }
+        self.search(lo, mid, range, found);
+        let (id, shift) = self.shifts[mid];
+        if shift.0 > range.1 {
+            // and everything to the right starts after it ends
+            return;
+        }
+        if shift.overlaps(&range) {
+            found.push((id, shift));
+        }
+        self.search(mid + 1, hi, range, found);
+    }
+}
+
+#[test]
+fn test_matches_brute_force() {
+    let shifts: Vec<_> = (0..200)
+        .map(|n| {
+            let start = (n * 37) % 101;
+            let shift = Shift(start, start + (n * 13) % 17);
+            (ShiftId { line: n, index: 0 }, shift)
+        })
+        .collect();
+    let tree = IntervalTree::new(&shifts);
+
+    for start in 0..120 {
+        let range = Shift(start, start + start % 5);
+        let mut found = tree.overlapping(range);
+        found.sort_unstable_by_key(|&(id, _)| id);
+        let expected: Vec<_> = shifts.iter().copied().filter(|(_, shift)| shift.overlaps(&range)).collect();
+        assert_eq!(found, expected);
+
+        let mut containing = tree.containing(start);
+        containing.sort_unstable_by_key(|&(id, _)| id);
+        let expected: Vec<_> = shifts.iter().copied().filter(|(_, shift)| shift.contains(&Shift(start, start))).collect();
+        assert_eq!(containing, expected);
+    }
+}
@@ -1,70 +1,511 @@
-use std::env;
-use std::convert::From;
+mod chart;
+mod interval_set;
+mod interval_tree;
+mod optimize;
+mod region;
+mod sweep;
 
-struct Shift(usize, usize);
+use std::{env, fmt, io, process};
+use std::str::FromStr;
+
+use crate::chart::Chart;
+use crate::interval_set::IntervalSet;
+use crate::interval_tree::IntervalTree;
+use crate::optimize::reassign;
+use crate::region::analyse;
+use crate::sweep::{depth_profile, sweep, ShiftId};
+
+// anything that can number sections: any of the primitive integer types
+pub trait Section: Copy + Ord + fmt::Display + FromStr {
+    // how many sections there are from `start` to `end`, inclusive
+    fn count(start: Self, end: Self) -> usize;
+}
+
+macro_rules! impl_section {
+    ($($t:ty),*) => {
+        $(impl Section for $t {
+            fn count(start: Self, end: Self) -> usize {
+                (end as i128 - start as i128 + 1).clamp(0, usize::MAX as i128) as usize
+            }
+        })*
+    };
+}
+
+impl_section!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+// an inclusive range of sections. one that ends before it starts is empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shift<T = usize>(T, T);
+
+impl<T: Section> Shift<T> {
+    fn is_empty(&self) -> bool {
+        self.0 > self.1
+    }
+    fn len(&self) -> usize {
+        T::count(self.0, self.1)
+    }
+    fn contains(&self, other: &Shift<T>) -> bool {
+        other.is_empty() || (self.0 <= other.0 && self.1 >= other.1)
+    }
+    fn overlaps(&self, other: &Shift<T>) -> bool {
+        !self.intersection(other).is_empty()
+    }
+    fn intersection(&self, other: &Shift<T>) -> Shift<T> {
+        Shift(self.0.max(other.0), self.1.min(other.1))
+    }
+}
 
 impl Shift {
-    fn contains(&self, other: &Shift) -> bool {
-        self.0 <= other.0 && self.1 >= other.1
+    // taking a piece out of the middle of a shift leaves two, so this gives back a set
+    fn difference(&self, other: &Shift) -> IntervalSet {
+        if !self.overlaps(other) {
+            return IntervalSet::from_iter([*self]);
+        }
+        let mut pieces = vec![];
+        if other.0 > self.0 {
+            pieces.push(Shift(self.0, other.0 - 1));
+        }
+        if other.1 < self.1 {
+            pieces.push(Shift(other.1 + 1, self.1));
+        }
+        IntervalSet::from_iter(pieces)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Shift<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.0, self.1)
+    }
+}
+
+impl<T: Section> FromStr for Shift<T> {
+    type Err = ParseError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        parse_shift(code, 1)
+    }
+}
+
+// `column` is where `code` starts on its line, so errors can point at the right place
+fn parse_shift<T: Section>(code: &str, column: usize) -> Result<Shift<T>, ParseError> {
+    // a leading minus belongs to the first number rather than being the separator
+    let dash = code.get(1..).and_then(|rest| rest.find('-')).map(|n| n + 1);
+    let dash = dash.ok_or(ParseError::new(column, ParseErrorKind::MissingDash))?;
+    let (start, end) = (&code[..dash], &code[dash + 1..]);
+    Ok(Shift(
+        parse_section(start, column)?,
+        parse_section(end, column + dash + 1)?,
+    ))
+}
+
+fn parse_section<T: Section>(code: &str, column: usize) -> Result<T, ParseError> {
+    code.parse().map_err(|_| {
+        let kind = if code.starts_with('-') {
+            ParseErrorKind::Negative
+        } else {
+            ParseErrorKind::NotANumber(code.to_string())
+        };
+        ParseError::new(column, kind)
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseErrorKind {
+    MissingDash,
+    Negative,
+    NotANumber(String),
+    WrongDimensions { expected: usize, found: usize },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    // only known once the error has made it up to `parse_input`
+    line: Option<usize>,
+    column: usize,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn new(column: usize, kind: ParseErrorKind) -> Self {
+        Self { line: None, column, kind }
     }
-    fn overlaps(&self, other: &Shift) -> bool {
-        self.1 >= other.0 && other.1 >= self.0
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line)?;
+        }
+        write!(f, "column {}: ", self.column)?;
+        match &self.kind {
+            ParseErrorKind::MissingDash => write!(f, "expected a shift like `2-4`"),
+            ParseErrorKind::Negative => write!(f, "sections can't be negative"),
+            ParseErrorKind::NotANumber(code) => write!(f, "expected a section number, found `{}`", code),
+            ParseErrorKind::WrongDimensions { expected, found } => {
+                write!(f, "expected {} dimensions, found {}", expected, found)
+            }
+        }
     }
 }
 
-impl From<&str> for Shift {
-    fn from(code: &str) -> Self {
-        let mut split_code = code.split("-");
-        Self(
-            split_code.next().unwrap().parse().unwrap(),
-            split_code.next().unwrap().parse().unwrap()
-        )
+// the shifts on one line of the input. the puzzle only ever has pairs, but there can be any number.
+struct ShiftGroup(Vec<Shift>);
+
+impl ShiftGroup {
+    // every other shift in the group, for each shift
+    fn others(&self) -> impl Iterator<Item = (&Shift, Vec<&Shift>)> {
+        self.0.iter().enumerate().map(|(n, shift)| {
+            (shift, self.0.iter().enumerate().filter(|&(m, _)| m != n).map(|(_, other)| other).collect())
+        })
+    }
+    fn any_contained(&self) -> bool {
+        self.others().any(|(shift, others)| others.iter().any(|other| other.contains(shift)))
+    }
+    fn any_overlapping(&self) -> bool {
+        self.others().any(|(shift, others)| others.iter().any(|other| other.overlaps(shift)))
+    }
+    // the shifts nobody would miss, because the rest of the group already works all of their sections
+    fn covered_by_others(&self) -> Vec<Shift> {
+        self.others()
+            .filter(|(shift, others)| others.iter().copied().copied().collect::<IntervalSet>().contains(shift))
+            .map(|(shift, _)| *shift)
+            .collect()
+    }
+    fn union(&self) -> IntervalSet {
+        self.0.iter().copied().collect()
+    }
+    fn claimed_by_at_least(&self, k: usize) -> IntervalSet {
+        depth_profile(&self.0).into_iter().filter(|&(_, depth)| depth >= k).map(|(shift, _)| shift).collect()
+    }
+    fn max_depth(&self) -> usize {
+        depth_profile(&self.0).into_iter().map(|(_, depth)| depth).max().unwrap_or(0)
     }
 }
 
-struct ShiftPair(Shift, Shift);
+impl FromStr for ShiftGroup {
+    type Err = ParseError;
 
-impl From<&str> for ShiftPair {
-    fn from(code: &str) -> Self {
-        let mut split_code = code.split(",");
-        Self(
-            Shift::from(split_code.next().unwrap()),
-            Shift::from(split_code.next().unwrap())
-        )
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let mut column = 1;
+        let shifts = code.split(',').map(|shift_code| {
+            let shift = parse_shift(shift_code, column);
+            column += shift_code.len() + 1;
+            shift
+        }).collect::<Result<_, _>>()?;
+        Ok(Self(shifts))
     }
 }
 
+impl fmt::Display for ShiftGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shifts: Vec<_> = self.0.iter().map(Shift::to_string).collect();
+        write!(f, "{}", shifts.join(","))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Strictness {
+    // stop at the first line that doesn't parse
+    Strict,
+    // leave out lines that don't parse, but keep track of why
+    Lenient,
+}
+
+struct Input {
+    // each group along with its line number, counting from zero
+    groups: Vec<(usize, ShiftGroup)>,
+    skipped: Vec<ParseError>,
+}
+
+impl Input {
+    fn groups(&self) -> impl Iterator<Item = &ShiftGroup> {
+        self.groups.iter().map(|(_, group)| group)
+    }
+}
+
+fn parse_input(input: &str, strictness: Strictness) -> Result<Input, ParseError> {
+    let mut parsed = Input { groups: vec![], skipped: vec![] };
+
+    for (line, code) in input.split('\n').enumerate() {
+        let code = code.trim_end();
+        if code.is_empty() {
+            // blank lines, like the one after a trailing newline, aren't mistakes
+            continue;
+        }
+
+        match code.parse() {
+            Ok(group) => parsed.groups.push((line, group)),
+            Err(err) => {
+                let err = ParseError { line: Some(line + 1), ..err };
+                match strictness {
+                    Strictness::Strict => return Err(err),
+                    Strictness::Lenient => parsed.skipped.push(err),
+                }
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+    let strictness = if flag("--lenient") { Strictness::Lenient } else { Strictness::Strict };
+    let (compressed, svg) = (flag("--compressed"), flag("--svg"));
+    let args: Vec<_> = args.iter().filter(|arg| !arg.starts_with("--")).cloned().collect();
     let filename = &args[1];
 
     let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", a(input));
 
-    let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", b(input));
+    // rectangles and boxes aren't shift groups, so they get parsed separately
+    let dimensions = match args.get(2).map(String::as_str) {
+        Some("area") => Some(2),
+        Some("volume") => Some(3),
+        _ => None,
+    };
+    if let Some(dimensions) = dimensions {
+        let report = if dimensions == 2 { analyse::<i64, 2>(&input, strictness) } else { analyse::<i64, 3>(&input, strictness) };
+        match report {
+            Ok(report) => {
+                for err in &report.skipped {
+                    eprintln!("skipped {}", err);
+                }
+                println!("{}", report.contained);
+                println!("{}", report.overlapping);
+                println!("{} overlapping", report.overlap_size);
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let input = match parse_input(&input, strictness) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+    for err in &input.skipped {
+        eprintln!("skipped {}", err);
+    }
+
+    match args.get(2).map(String::as_str) {
+        Some("coverage") => {
+            let covered = coverage(&input);
+            // everything from the first section up to the last one anybody works on
+            let last = covered.iter().last().map_or(0, |shift| shift.1);
+
+            println!("covered: {} sections in {}", covered.len(), covered);
+            println!("gaps: {}", or_none(&covered.gaps()));
+            println!("uncovered: {}", or_none(&covered.uncovered(Shift(1, last))));
+        }
+        Some("groups") => {
+            let k = args.get(3).map_or(2, |k| k.parse().unwrap());
+
+            for group in input.groups() {
+                let covered: Vec<_> = group.covered_by_others().iter().map(Shift::to_string).collect();
+                println!(
+                    "{}: covers {}, redundant [{}], claimed by {}+ {}, max depth {}",
+                    group, group.union(), covered.join(", "), k, or_none(&group.claimed_by_at_least(k)), group.max_depth(),
+                );
+            }
+        }
+        Some("sweep") => {
+            let report = sweep(&identified_shifts(&input));
+
+            println!("peak: {} shifts at once, on sections {}", report.peak, or_none(&report.peak_sections));
+            println!("{} overlapping pairs:", report.overlapping_pairs.len());
+            for (first, second) in &report.overlapping_pairs {
+                println!("    {} and {}", first, second);
+            }
+        }
+        // reads `start-end` (shifts overlapping that range) or `section` (shifts working it) from stdin
+        Some("query") => {
+            let tree = IntervalTree::new(&identified_shifts(&input));
+
+            for query in io::stdin().lines() {
+                let query = query.unwrap();
+                let found = if query.contains('-') {
+                    query.trim().parse().map(|range| tree.overlapping(range))
+                } else {
+                    parse_section(query.trim(), 1).map(|section| tree.containing(section))
+                };
+
+                match found {
+                    Ok(found) => {
+                        println!("{}: {} shifts", query, found.len());
+                        for (id, shift) in found {
+                            println!("    {} {}", id, shift);
+                        }
+                    }
+                    Err(err) => println!("{}: {}", query, err),
+                }
+            }
+        }
+        Some("chart") => {
+            let chart = Chart::new(input.groups(), compressed);
+            print!("{}", if svg { chart.to_svg() } else { chart.to_text() });
+        }
+        Some("optimize") => {
+            let mut total_saved = 0;
+
+            for group in input.groups() {
+                let reassignment = reassign(group);
+                let trimmed: Vec<_> = reassignment.trimmed.iter().map(|shift| {
+                    if shift.is_empty() { "none".to_string() } else { shift.to_string() }
+                }).collect();
+
+                println!("{} -> {} saves {}", group, trimmed.join(","), reassignment.saved());
+                total_saved = reassignment.saved().saturating_add(total_saved);
+            }
+            println!("{} sections saved in total", total_saved);
+        }
+        _ => {
+            println!("{}", a(&input));
+            println!("{}", b(&input));
+        }
+    }
+}
+
+// every shift in the input, labelled with where it came from
+fn identified_shifts(input: &Input) -> Vec<(ShiftId, Shift)> {
+    input.groups.iter().flat_map(|(line, group)| {
+        group.0.iter().enumerate().map(move |(index, &shift)| (ShiftId { line: *line, index }, shift))
+    }).collect()
 }
 
-fn a(input: String) -> usize {
-    input.split("\n").map(ShiftPair::from).filter(
-        |shift_pair| shift_pair.0.contains(&shift_pair.1) || shift_pair.1.contains(&shift_pair.0)
-    ).count()
+fn or_none(set: &IntervalSet) -> String {
+    if set.is_empty() { "none".to_string() } else { set.to_string() }
 }
 
-fn b(input: String) -> usize {
-    input.split("\n").map(ShiftPair::from).filter(
-        |shift_pair| shift_pair.0.overlaps(&shift_pair.1)
-    ).count()
+fn coverage(input: &Input) -> IntervalSet {
+    input.groups().flat_map(|shift_group| shift_group.union()).collect()
+}
+
+fn a(input: &Input) -> usize {
+    input.groups().filter(|shift_group| shift_group.any_contained()).count()
+}
+
+fn b(input: &Input) -> usize {
+    input.groups().filter(|shift_group| shift_group.any_overlapping()).count()
 }
 
 #[test]
 fn test_example_a() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
-    assert_eq!(a(input), 2);
+    let input = parse_input(&input, Strictness::Strict).unwrap();
+    assert_eq!(a(&input), 2);
 }
 
 #[test]
 fn test_example_b() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
-    assert_eq!(b(input), 4);
-}
\ No newline at end of file
+    let input = parse_input(&input, Strictness::Strict).unwrap();
+    assert_eq!(b(&input), 4);
+}
+
+#[test]
+fn test_shift_algebra() {
+    assert_eq!(Shift(2, 8).intersection(&Shift(3, 7)), Shift(3, 7));
+    assert!(Shift(2, 4).intersection(&Shift(6, 8)).is_empty());
+    assert_eq!(Shift(2, 4).len(), 3);
+    assert_eq!(Shift(5, 3).len(), 0);
+
+    // empty shifts fit inside anything but overlap nothing
+    assert!(Shift(6, 6).contains(&Shift(9, 1)));
+    assert!(!Shift(1, 9).overlaps(&Shift(5, 3)));
+
+    assert_eq!(IntervalSet::from_iter([Shift(2, 4), Shift(5, 7)]).to_string(), "2-7");
+    assert_eq!(IntervalSet::from_iter([Shift(2, 4), Shift(6, 8)]).to_string(), "2-4, 6-8");
+    assert_eq!(Shift(2, 8).difference(&Shift(4, 5)).to_string(), "2-3, 6-8");
+    assert_eq!(Shift(2, 8).difference(&Shift(0, 5)).to_string(), "6-8");
+    assert_eq!(Shift(2, 8).difference(&Shift(1, 9)).to_string(), "");
+}
+
+#[test]
+fn test_example_coverage() {
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let input = parse_input(&input, Strictness::Strict).unwrap();
+    let covered = coverage(&input);
+    assert_eq!(covered.len(), 8);
+    assert_eq!(covered.to_string(), "2-9");
+    assert_eq!(covered.uncovered(Shift(1, 10)).to_string(), "1-1, 10-10");
+}
+
+#[test]
+fn test_groups() {
+    let group = "2-8,3-4,6-9,1-2".parse::<ShiftGroup>().unwrap();
+    assert_eq!(group.covered_by_others(), [Shift(3, 4)]);
+    assert_eq!(group.union().to_string(), "1-9");
+    assert!(ShiftGroup(vec![]).union().is_empty());
+    assert_eq!(group.claimed_by_at_least(2).to_string(), "2-4, 6-8");
+    assert_eq!(group.claimed_by_at_least(3).to_string(), "");
+    assert_eq!(group.max_depth(), 2);
+    assert!(group.any_contained());
+}
+
+#[test]
+fn test_example_sweep() {
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let input = parse_input(&input, Strictness::Strict).unwrap();
+    let shifts = identified_shifts(&input);
+    let report = sweep(&shifts);
+
+    let mut expected = vec![];
+    for (n, (first, first_shift)) in shifts.iter().enumerate() {
+        for (second, second_shift) in &shifts[n + 1..] {
+            if first_shift.overlaps(second_shift) {
+                expected.push((*first.min(second), *first.max(second)));
+            }
+        }
+    }
+    let mut found: Vec<_> = report.overlapping_pairs.iter().map(|&(x, y)| (x.min(y), x.max(y))).collect();
+    expected.sort();
+    found.sort();
+    assert_eq!(found, expected);
+
+    assert_eq!(report.peak, 8);
+    assert_eq!(report.peak_sections.to_string(), "6-6");
+}
+
+#[test]
+fn test_shifts_to_the_end() {
+    let group = format!("5-{0},{0}-{0},1-3", usize::MAX).parse::<ShiftGroup>().unwrap();
+    assert_eq!(group.max_depth(), 2);
+    assert_eq!(group.claimed_by_at_least(2).to_string(), format!("{0}-{0}", usize::MAX));
+
+    let shifts: Vec<_> = group.0.iter().enumerate().map(|(index, &shift)| (ShiftId { line: 0, index }, shift)).collect();
+    let report = sweep(&shifts);
+    assert_eq!(report.peak, 2);
+    assert_eq!(report.overlapping_pairs.len(), 1);
+
+    let reassignment = reassign(&group);
+    assert_eq!(reassignment.trimmed, [Shift(5, usize::MAX), Shift(1, 0), Shift(1, 3)]);
+    assert_eq!(reassignment.saved(), 1);
+    assert_eq!(reassign(&format!("1-{0},1-{0}", usize::MAX).parse().unwrap()).saved(), usize::MAX);
+
+    let chart = Chart::new([&group].into_iter(), true);
+    assert!(chart.to_text().ends_with(&format!("columns: 1-3 4-4 5-{} {1}-{1}\n", usize::MAX - 1, usize::MAX)));
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = |line, column, kind| ParseError { line: Some(line), column, kind };
+    let input = "2-4,6-8\n2-3,4\n5-7,-7-9\n2-8,3--7\n2-x,4-6\n\n";
+
+    assert_eq!(parse_input(input, Strictness::Strict).err(), Some(error(2, 5, ParseErrorKind::MissingDash)));
+
+    let lenient = parse_input(input, Strictness::Lenient).unwrap();
+    assert_eq!(lenient.groups.len(), 1);
+    assert_eq!(lenient.skipped, [
+        error(2, 5, ParseErrorKind::MissingDash),
+        error(3, 5, ParseErrorKind::Negative),
+        error(4, 7, ParseErrorKind::Negative),
+        error(5, 3, ParseErrorKind::NotANumber("x".to_string())),
+    ]);
+}
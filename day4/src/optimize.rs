@@ -0,0 +1,58 @@
+// Trimming shifts so nobody in a group works a section someone else is already doing.
+// Each shift can only shrink (possibly to nothing), never split, and the group as a whole still
+// covers exactly the same sections.
+
+use crate::{Shift, ShiftGroup};
+
+pub struct Reassignment {
+    pub original: Vec<Shift>,
+    // in the same order as `original`
+    pub trimmed: Vec<Shift>,
+}
+
+impl Reassignment {
+    // sections that were being worked more than once
+    pub fn saved(&self) -> usize {
+        // shifts running up to usize::MAX can save more than fits, so this stops there
+        self.original.iter().zip(&self.trimmed)
+            .map(|(original, trimmed)| original.len() - trimmed.len())
+            .fold(0, usize::saturating_add)
+    }
+}
+
+// going through the shifts by start, each one keeps only what lies past everything before it.
+// the sections between its start and that point are already covered (the earlier shifts all
+// started no later), so what's left is one unbroken range and no section ends up worked twice,
+// which is as little overlap as there can be.
+pub fn reassign(group: &ShiftGroup) -> Reassignment {
+    let original = group.0.clone();
+    let mut order: Vec<_> = (0..original.len()).filter(|&n| !original[n].is_empty()).collect();
+    order.sort_unstable_by_key(|&n| (original[n].0, std::cmp::Reverse(original[n].1)));
+
+    let mut trimmed = vec![Shift(1, 0); original.len()];
+    let mut covered_to = None;
+    for n in order {
+        let shift = original[n];
+        // once everything up to usize::MAX is covered there's nothing left to keep
+        let start = covered_to.map_or(Some(shift.0), |covered_to: usize| {
+            covered_to.checked_add(1).map(|next| shift.0.max(next))
+        });
+        if let Some(start) = start.filter(|&start| start <= shift.1) {
+            trimmed[n] = Shift(start, shift.1);
+            covered_to = Some(shift.1);
+        }
+    }
+
+    Reassignment { original, trimmed }
+}
+
+#[test]
+fn test_reassign() {
+    let reassignment = reassign(&"3-7,2-8,5-9,1-1".parse().unwrap());
+    assert_eq!(reassignment.trimmed, [Shift(1, 0), Shift(2, 8), Shift(9, 9), Shift(1, 1)]);
+    assert_eq!(reassignment.saved(), 9);
+    assert_eq!(
+        ShiftGroup(reassignment.trimmed).union(),
+        ShiftGroup(reassignment.original).union(),
+    );
+}
@@ -0,0 +1,141 @@
+// The same containment and overlap questions, asked about axis-aligned rectangles and boxes
+// instead of ranges. A region is one shift per dimension, so a rectangle is written `x1-x2,y1-y2`
+// and a box `x1-x2,y1-y2,z1-z2`. Regions on the same line are separated by `;`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{parse_shift, ParseError, ParseErrorKind, Section, Shift, Strictness};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region<T, const D: usize>([Shift<T>; D]);
+
+impl<T: Section, const D: usize> Region<T, D> {
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().any(Shift::is_empty)
+    }
+    // area for rectangles, volume for boxes
+    pub fn size(&self) -> usize {
+        self.0.iter().map(Shift::len).fold(1, usize::saturating_mul)
+    }
+    pub fn contains(&self, other: &Self) -> bool {
+        other.is_empty() || self.0.iter().zip(&other.0).all(|(mine, theirs)| mine.contains(theirs))
+    }
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+    pub fn intersection(&self, other: &Self) -> Self {
+        Region(std::array::from_fn(|n| self.0[n].intersection(&other.0[n])))
+    }
+}
+
+fn parse_region<T: Section, const D: usize>(code: &str, column: usize) -> Result<Region<T, D>, ParseError> {
+    let mut shift_column = column;
+    let shifts = code.split(',').map(|shift_code| {
+        let shift = parse_shift(shift_code, shift_column);
+        shift_column += shift_code.len() + 1;
+        shift
+    }).collect::<Result<Vec<_>, _>>()?;
+
+    let found = shifts.len();
+    let shifts = shifts.try_into().map_err(|_| ParseError::new(column, ParseErrorKind::WrongDimensions { expected: D, found }))?;
+    Ok(Region(shifts))
+}
+
+impl<T: Section, const D: usize> FromStr for Region<T, D> {
+    type Err = ParseError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        parse_region(code, 1)
+    }
+}
+
+impl<T: fmt::Display, const D: usize> fmt::Display for Region<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shifts: Vec<_> = self.0.iter().map(Shift::to_string).collect();
+        write!(f, "{}", shifts.join(","))
+    }
+}
+
+fn parse_region_line<T: Section, const D: usize>(code: &str) -> Result<Vec<Region<T, D>>, ParseError> {
+    let mut column = 1;
+    code.split(';').map(|region_code| {
+        let region = parse_region(region_code, column);
+        column += region_code.len() + 1;
+        region
+    }).collect()
+}
+
+// parts a and b over regions, plus how much space the overlaps take up
+#[derive(Debug, Default, PartialEq)]
+pub struct RegionReport {
+    pub contained: usize,
+    pub overlapping: usize,
+    pub overlap_size: usize,
+    // lines left out in lenient mode
+    pub skipped: Vec<ParseError>,
+}
+
+pub fn analyse<T: Section, const D: usize>(input: &str, strictness: Strictness) -> Result<RegionReport, ParseError> {
+    let mut report = RegionReport::default();
+
+    for (line, code) in input.split('\n').enumerate() {
+        let code = code.trim_end();
+        if code.is_empty() {
+            continue;
+        }
+        let regions = match parse_region_line::<T, D>(code) {
+            Ok(regions) => regions,
+            Err(err) => {
+                let err = ParseError { line: Some(line + 1), ..err };
+                match strictness {
+                    Strictness::Strict => return Err(err),
+                    Strictness::Lenient => {
+                        report.skipped.push(err);
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let mut contained = false;
+        let mut overlapping = false;
+        for (n, region) in regions.iter().enumerate() {
+            for other in &regions[n + 1..] {
+                contained |= region.contains(other) || other.contains(region);
+                overlapping |= region.overlaps(other);
+                // regions that miss each other are empty on some axis, so their intersection is no size
+                report.overlap_size += region.intersection(other).size();
+            }
+        }
+        report.contained += usize::from(contained);
+        report.overlapping += usize::from(overlapping);
+    }
+
+    Ok(report)
+}
+
+#[test]
+fn test_rectangles() {
+    let big: Region<i64, 2> = "-2-2,-2-2".parse().unwrap();
+    let small: Region<i64, 2> = "0-1,-1-0".parse().unwrap();
+    let apart: Region<i64, 2> = "3-4,0-0".parse().unwrap();
+    assert_eq!(big.size(), 25);
+    assert!(big.contains(&small));
+    assert!(!big.overlaps(&apart));
+    assert_eq!(big.intersection(&"1-5,1-5".parse().unwrap()).to_string(), "1-2,1-2");
+
+    let input = "-2-2,-2-2;0-1,-1-0\n0-1,0-1;1-2,1-2\n0-0,0-0;1-1,1-1\n";
+    assert_eq!(analyse::<i64, 2>(input, Strictness::Strict), Ok(RegionReport { contained: 1, overlapping: 2, overlap_size: 5, skipped: vec![] }));
+    assert_eq!(
+        analyse::<i64, 3>("0-1,0-1", Strictness::Strict).err().map(|err| err.to_string()),
+        Some("line 1, column 1: expected 3 dimensions, found 2".to_string()),
+    );
+
+    // lenient mode carries on past the bad line
+    let report = analyse::<i64, 2>("0-1,0-1;1-2,1-2
+0-1
+0-0,0-0;0-0,0-0", Strictness::Lenient).unwrap();
+    assert_eq!((report.contained, report.overlapping, report.overlap_size), (1, 2, 2));
+    assert_eq!(report.skipped.iter().map(|err| err.to_string()).collect::<Vec<_>>(), ["line 2, column 1: expected 2 dimensions, found 1"]);
+}
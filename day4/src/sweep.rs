@@ -0,0 +1,77 @@
+// Sweeping across the section axis, keeping track of how many shifts are active at each point,
+// so questions about all the shifts at once don't need every pair compared.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use crate::interval_set::IntervalSet;
+use crate::Shift;
+
+// the sections worked by at least one shift, cut into runs where the number of shifts working
+// them stays the same. sorting the start and end points makes this O(n log n).
+pub fn depth_profile(shifts: &[Shift]) -> Vec<(Shift, usize)> {
+    // a shift starts counting at its first section and stops the section after its last, which
+    // is one past usize::MAX for a shift that runs to the end
+    let mut events: Vec<(u128, isize)> = shifts
+        .iter()
+        .filter(|shift| !shift.is_empty())
+        .flat_map(|shift| [(shift.0 as u128, 1), (shift.1 as u128 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut profile = vec![];
+    let mut depth = 0;
+    for (n, &(section, change)) in events.iter().enumerate() {
+        depth += change;
+        let next = events.get(n + 1).map_or(section, |&(next, _)| next);
+        if depth > 0 && next > section {
+            profile.push((Shift(section as usize, (next - 1) as usize), depth as usize));
+        }
+    }
+    profile
+}
+
+// where a shift came from: its line in the input and its position on that line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ShiftId {
+    pub line: usize,
+    pub index: usize,
+}
+
+impl fmt::Display for ShiftId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.index + 1)
+    }
+}
+
+pub struct SweepReport {
+    pub overlapping_pairs: Vec<(ShiftId, ShiftId)>,
+    // the most shifts working any one section, and the sections where that happens
+    pub peak: usize,
+    pub peak_sections: IntervalSet,
+}
+
+// compares every shift in the input with every other, in O(n log n) plus the number of pairs found
+pub fn sweep(shifts: &[(ShiftId, Shift)]) -> SweepReport {
+    let mut by_start: Vec<_> = shifts.iter().filter(|(_, shift)| !shift.is_empty()).collect();
+    by_start.sort_unstable_by_key(|(id, shift)| (shift.0, *id));
+
+    // shifts that have started, soonest finishing first. anything still in here once the ones that
+    // finished before the current start are gone overlaps the current shift.
+    let mut active = BinaryHeap::new();
+    let mut overlapping_pairs = vec![];
+    for &&(id, shift) in &by_start {
+        while active.peek().is_some_and(|&Reverse((end, _))| end < shift.0) {
+            active.pop();
+        }
+        overlapping_pairs.extend(active.iter().map(|&Reverse((_, other))| (other, id)));
+        active.push(Reverse((shift.1, id)));
+    }
+
+    let profile = depth_profile(&shifts.iter().map(|&(_, shift)| shift).collect::<Vec<_>>());
+    let peak = profile.iter().map(|&(_, depth)| depth).max().unwrap_or(0);
+    let peak_sections = profile.into_iter().filter(|&(_, depth)| depth == peak).map(|(shift, _)| shift).collect();
+
+    SweepReport { overlapping_pairs, peak, peak_sections }
+}
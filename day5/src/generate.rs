@@ -0,0 +1,75 @@
+// Random puzzle inputs, for fuzzing the parser and for giving the simulation something big to chew on.
+// The same seed always gives the same puzzle.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{Crate, Instruction};
+
+pub struct PuzzleConfig {
+    pub stacks: usize,
+    pub crates: usize,
+    pub moves: usize,
+    pub max_label_len: usize,
+}
+
+impl Default for PuzzleConfig {
+    fn default() -> Self {
+        // about the size of a real puzzle input
+        Self { stacks: 9, crates: 50, moves: 500, max_label_len: 1 }
+    }
+}
+
+pub fn generate(seed: u64, config: &PuzzleConfig) -> String {
+    assert!(config.stacks >= 2, "need at least two stacks to move crates between");
+    assert!(config.crates >= 1, "need at least one crate to draw");
+    assert!(config.max_label_len >= 1);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut stacks = vec![vec![]; config.stacks];
+    for _ in 0..config.crates {
+        let label_len = rng.gen_range(1..=config.max_label_len);
+        let label = (0..label_len).map(|_| rng.gen_range('A'..='Z')).collect();
+        stacks[rng.gen_range(0..config.stacks)].push(Crate(label));
+    }
+
+    // only the heights matter for keeping the moves valid
+    let mut heights: Vec<_> = stacks.iter().map(Vec::len).collect();
+    let mut instructions = Vec::with_capacity(config.moves);
+    for _ in 0..config.moves {
+        let non_empty: Vec<_> = (0..config.stacks).filter(|&n| heights[n] > 0).collect();
+        let src = non_empty[rng.gen_range(0..non_empty.len())];
+        let dst = (src + rng.gen_range(1..config.stacks)) % config.stacks;
+        let quantity = rng.gen_range(1..=heights[src]);
+
+        heights[src] -= quantity;
+        heights[dst] += quantity;
+        instructions.push(Instruction { quantity, src, dst });
+    }
+
+    let mut puzzle = draw(&stacks);
+    puzzle.push('\n');
+    for instruction in &instructions {
+        puzzle.push('\n');
+        puzzle.push_str(&instruction.to_string());
+    }
+    puzzle
+}
+
+// the inverse of the drawing half of `parse_input`: every column is as wide as the widest crate,
+// with the stack numbers centred underneath
+pub fn draw(stacks: &[Vec<Crate>]) -> String {
+    let width = stacks.iter().flatten().map(|c| c.0.len() + 2).max().unwrap_or(3);
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut lines: Vec<_> = (0..height).rev().map(|level| {
+        stacks.iter().map(|stack| match stack.get(level) {
+            Some(c) => format!("{:^width$}", format!("[{}]", c.0)),
+            None => " ".repeat(width),
+        }).collect::<Vec<_>>().join(" ")
+    }).collect();
+
+    lines.push((1..=stacks.len()).map(|n| format!("{:^width$}", n)).collect::<Vec<_>>().join(" "));
+    lines.join("\n")
+}
@@ -0,0 +1,55 @@
+// Following individual crates through the simulation rather than just reading off the tops.
+// Labels needn't be unique, so crates are told apart by where they started.
+
+use crate::rope::Rope;
+use crate::{Crane, Crate, Instruction, Stack};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    pub stack: usize,
+    // counted from the bottom of the stack
+    pub height: usize,
+}
+
+pub struct CrateHistory {
+    pub label: Crate,
+    pub start: Position,
+    pub end: Position,
+    // indices into the instruction list, in the order they were carried out
+    pub moves: Vec<usize>,
+}
+
+pub struct History {
+    pub stacks: Vec<Vec<Crate>>,
+    pub crates: Vec<CrateHistory>,
+}
+
+pub fn simulate_with_history(stacks: &[Stack], instructions: &[Instruction], crane: Crane) -> History {
+    let mut crates = vec![];
+    let mut id_stacks: Vec<Rope<usize>> = stacks.iter().enumerate().map(|(stack, contents)| {
+        contents.iter().enumerate().map(|(height, label)| {
+            let start = Position { stack, height };
+            crates.push(CrateHistory { label: label.clone(), start, end: start, moves: vec![] });
+            crates.len() - 1
+        }).collect()
+    }).collect();
+
+    // the same moves as the plain simulation, but on crate ids, noting down everything that was picked up
+    for (n, instruction) in instructions.iter().enumerate() {
+        let block = crane.lift(&mut id_stacks[instruction.src], instruction.quantity);
+        for &id in block.iter() {
+            crates[id].moves.push(n);
+        }
+        id_stacks[instruction.dst].append(block);
+    }
+
+    for (stack, ids) in id_stacks.iter().enumerate() {
+        for (height, &id) in ids.iter().enumerate() {
+            crates[id].end = Position { stack, height };
+        }
+    }
+
+    let stacks = id_stacks.iter().map(|ids| ids.iter().map(|&id| crates[id].label.clone()).collect()).collect();
+
+    History { stacks, crates }
+}
@@ -1,17 +1,29 @@
 // Based on https://fasterthanli.me/series/advent-of-code-2022/part-5
 
+mod generate;
+mod history;
+mod plan;
+mod rope;
+
 use std::{env, fmt};
+use std::str::FromStr;
+use std::time::Instant;
 
-use itertools::Itertools;
-use nom::branch::alt;
-use nom::bytes::complete::{tag, take, take_while1};
+use nom::bytes::complete::{tag, take_till1, take_while1};
+use nom::character::complete::space0;
+use nom::error::{Error, ErrorKind};
 use nom::IResult;
 use nom::sequence::{delimited, preceded, tuple};
-use nom::combinator::{all_consuming, map, map_res, opt};
+use nom::combinator::{all_consuming, map, map_res};
 use nom::Finish;
 
-#[derive(Clone)]
-struct Crate(char);
+use crate::generate::{generate, PuzzleConfig};
+use crate::history::simulate_with_history;
+use crate::plan::{plan, Goal};
+use crate::rope::Rope;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Crate(String);
 
 impl fmt::Debug for Crate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -20,34 +32,38 @@ impl fmt::Debug for Crate {
 }
 
 fn parse_crate(i: &str) -> IResult<&str, Crate> {
-    let label_parser = delimited(tag("["), take(1_usize), tag("]"));
-    let crate_from_label = |s: &str| Crate(s.chars().next().unwrap());
+    let label_parser = delimited(tag("["), take_till1(|c| c == ']'), tag("]"));
+    let crate_from_label = |s: &str| Crate(s.to_string());
 
     map(label_parser, crate_from_label)(i)
 }
 
-fn parse_hole(i: &str) -> IResult<&str, ()> {
-    map(tag("   "), drop)(i)
-}
-
-fn parse_crate_or_hole(i: &str) -> IResult<&str, Option<Crate>> {
-    alt((map(parse_crate, Some), map(parse_hole, |_| None)))(i)
-}
-
-fn parse_crate_line(i: &str) -> IResult<&str, Vec<Option<Crate>>> {
-    let (mut i, c) = parse_crate_or_hole(i)?;
-    let mut v = vec![c];
+// labels can be any width, so rather than assuming three-character cells each crate is put in
+// the stack whose number in the footer is closest to it. stacks left without a crate are holes.
+fn parse_crate_line<'a>(i: &'a str, centres: &[usize]) -> IResult<&'a str, Vec<Option<Crate>>> {
+    let mut v = vec![None; centres.len()];
+    let mut rest = i;
 
     loop {
-        let (next_i, maybe_c) = opt(preceded(tag(" "), parse_crate_or_hole))(i)?;
-        match maybe_c {
-            Some(c) => v.push(c),
-            None => break,
+        let (next_rest, _) = space0(rest)?;
+        if next_rest.is_empty() {
+            break;
+        }
+        let start = i.len() - next_rest.len();
+        let (next_rest, c) = parse_crate(next_rest)?;
+        let end = i.len() - next_rest.len();
+
+        let centre = (start + end) / 2;
+        let column = (0..centres.len()).min_by_key(|&n| centres[n].abs_diff(centre)).unwrap();
+        if v[column].is_some() {
+            // two crates side by side in the same stack
+            return Err(nom::Err::Error(Error::new(next_rest, ErrorKind::Verify)));
         }
-        i = next_i;
+        v[column] = Some(c);
+        rest = next_rest;
     }
 
-    Ok((i, v))
+    Ok((rest, v))
 }
 
 fn parse_number(i: &str) -> IResult<&str, usize> {
@@ -56,13 +72,43 @@ fn parse_number(i: &str) -> IResult<&str, usize> {
     })(i)
 }
 
-#[derive(Debug)]
+// the numbered line under the drawing, giving the position of the middle of each stack
+fn parse_footer(i: &str) -> IResult<&str, Vec<usize>> {
+    let mut centres = vec![];
+    let mut rest = i;
+
+    loop {
+        let (next_rest, _) = space0(rest)?;
+        if next_rest.is_empty() {
+            break;
+        }
+        let start = i.len() - next_rest.len();
+        let (next_rest, _) = parse_number(next_rest)?;
+        let end = i.len() - next_rest.len();
+        centres.push((start + end) / 2);
+        rest = next_rest;
+    }
+
+    if centres.is_empty() {
+        return Err(nom::Err::Error(Error::new(i, ErrorKind::Digit)));
+    }
+
+    Ok((rest, centres))
+}
+
+#[derive(Clone, Debug)]
 struct Instruction {
     quantity: usize,
     src: usize,
     dst: usize
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.quantity, self.src + 1, self.dst + 1)
+    }
+}
+
 fn parse_instruction(i: &str) -> IResult<&str, Instruction> {
     map(
         tuple((
@@ -74,6 +120,83 @@ fn parse_instruction(i: &str) -> IResult<&str, Instruction> {
     )(i)
 }
 
+type Stack = Rope<Crate>;
+
+#[derive(Clone, Copy, Debug)]
+enum Crane {
+    CrateMover9000,
+    CrateMover9001,
+}
+
+impl Crane {
+    // takes `quantity` crates off the top of `stack`, in the order they will land on the destination.
+    // for ropes this is O(log n) however many crates are moved.
+    fn lift<S: Pile>(self, stack: &mut S, quantity: usize) -> S {
+        let mut block = stack.split_off(stack.len() - quantity);
+        if let Crane::CrateMover9000 = self {
+            // moving crates one at a time flips the block over
+            block.reverse();
+        }
+        block
+    }
+
+    fn execute<S: Pile>(self, stacks: &mut [S], instruction: &Instruction) {
+        let block = self.lift(&mut stacks[instruction.src], instruction.quantity);
+        stacks[instruction.dst].append(block);
+    }
+}
+
+impl FromStr for Crane {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "9000" => Ok(Crane::CrateMover9000),
+            "9001" => Ok(Crane::CrateMover9001),
+            _ => Err(format!("unknown crane model {}", s)),
+        }
+    }
+}
+
+// what the crane needs from a stack. the simulation uses ropes, but the planner wants plain
+// vectors it can hash.
+trait Pile {
+    fn len(&self) -> usize;
+    fn split_off(&mut self, at: usize) -> Self;
+    fn reverse(&mut self);
+    fn append(&mut self, other: Self);
+}
+
+impl<T> Pile for Rope<T> {
+    fn len(&self) -> usize {
+        Rope::len(self)
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        Rope::split_off(self, at)
+    }
+    fn reverse(&mut self) {
+        Rope::reverse(self)
+    }
+    fn append(&mut self, other: Self) {
+        Rope::append(self, other)
+    }
+}
+
+impl<T> Pile for Vec<T> {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        Vec::split_off(self, at)
+    }
+    fn reverse(&mut self) {
+        <[T]>::reverse(self)
+    }
+    fn append(&mut self, mut other: Self) {
+        Vec::append(self, &mut other)
+    }
+}
+
 fn transpose_rev<T>(rows: Vec<Vec<Option<T>>>) -> Vec<Vec<T>> {
     assert!(!rows.is_empty());
 
@@ -94,71 +217,136 @@ fn transpose_rev<T>(rows: Vec<Vec<Option<T>>>) -> Vec<Vec<T>> {
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let filename = &args[1];
 
-    let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", a(input));
+    match args[1].as_str() {
+        // day5 generate <seed> [stacks] [crates] [moves] [max label length]
+        "generate" => println!("{}", generate(args[2].parse().unwrap(), &puzzle_config(&args[3..]))),
+        // same arguments as generate, but time the simulation instead of printing the puzzle
+        "bench" => {
+            let input = generate(args[2].parse().unwrap(), &puzzle_config(&args[3..]));
+            for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+                let start = Instant::now();
+                let tops = run(input.clone(), crane);
+                println!("{:?}: {} on top in {:?}", crane, tops, start.elapsed());
+            }
+        }
+        // day5 <file> plan <9000|9001> tops <label>... | day5 <file> plan <9000|9001> drawing <file>
+        filename if args.get(2).map(String::as_str) == Some("plan") => {
+            let input = std::fs::read_to_string(filename).unwrap();
+            let crane: Crane = args[3].parse().unwrap();
+            let goal = match args[4].as_str() {
+                "tops" => Goal::Tops(args[5..].iter().map(|label| Crate(label.clone())).collect()),
+                "drawing" => Goal::Arrangement(to_vecs(&parse_input(&std::fs::read_to_string(&args[5]).unwrap()).0)),
+                other => panic!("unknown goal {}", other),
+            };
+
+            match plan(to_vecs(&parse_input(&input).0), &goal, crane, PLAN_STATE_LIMIT) {
+                Ok(instructions) => {
+                    for instruction in &instructions {
+                        println!("{}", instruction);
+                    }
+                    println!("{} moves", instructions.len());
+                }
+                Err(err) => println!("{}", err),
+            }
+        }
+        // day5 <file> history <9000|9001> [label]
+        filename if args.get(2).map(String::as_str) == Some("history") => {
+            let input = std::fs::read_to_string(filename).unwrap();
+            let crane: Crane = args[3].parse().unwrap();
+            let (stacks, instructions) = parse_input(&input);
+            let history = simulate_with_history(&stacks, &instructions, crane);
+
+            for (n, stack) in history.stacks.iter().enumerate() {
+                println!("{}: {:?}", n + 1, stack);
+            }
+
+            let wanted = args.get(4).map(|label| Crate(label.clone()));
+            for c in history.crates.iter().filter(|c| wanted.as_ref().is_none_or(|label| &c.label == label)) {
+                println!();
+                println!(
+                    "[{}] went from stack {} height {} to stack {} height {}",
+                    c.label.0, c.start.stack + 1, c.start.height + 1, c.end.stack + 1, c.end.height + 1,
+                );
+                for &n in &c.moves {
+                    println!("    {}: {}", n + 1, instructions[n]);
+                }
+            }
+        }
+        filename => {
+            let input = std::fs::read_to_string(filename).unwrap();
+            println!("{}", a(input));
 
-    let input = std::fs::read_to_string(filename).unwrap();
-    println!("{}", b(input));
+            let input = std::fs::read_to_string(filename).unwrap();
+            println!("{}", b(input));
+        }
+    }
 }
 
-fn a(input: String) -> String {
-    let mut lines = input.split('\n').into_iter();
-
-    let crate_lines: Vec<_> = (&mut lines).map_while(|line| {
-        all_consuming(parse_crate_line)(line).finish().ok().map(|(_, line)| line)
-    }).collect();
+// the search is exhaustive, so give up rather than eat all the memory on big drawings
+const PLAN_STATE_LIMIT: usize = 1_000_000;
 
-    // our result is by rows, but we need stacks, so transpose the Vec<Vec<Option<Crate>>>
-    let mut crate_stacks = transpose_rev(crate_lines);
+fn to_vecs(stacks: &[Stack]) -> Vec<Vec<Crate>> {
+    stacks.iter().map(|stack| stack.iter().cloned().collect()).collect()
+}
 
-    lines.next(); // consume separator
+fn puzzle_config(args: &[String]) -> PuzzleConfig {
+    let default = PuzzleConfig::default();
+    let arg = |n: usize, default: usize| args.get(n).map_or(default, |arg| arg.parse().unwrap());
 
-    for instruction in lines
-        .filter_map(|line| all_consuming(parse_instruction)(line).finish().ok())
-        .map(|instruction| instruction.1) {
-        for _ in 0..instruction.quantity {
-            let c = crate_stacks[instruction.src].pop().unwrap();
-            crate_stacks[instruction.dst].push(c);
-        }
+    PuzzleConfig {
+        stacks: arg(0, default.stacks),
+        crates: arg(1, default.crates),
+        moves: arg(2, default.moves),
+        max_label_len: arg(3, default.max_label_len),
     }
-
-    crate_stacks
-        .iter()
-        .filter_map(|stack| stack.last())
-        .map(|c| c.0)
-        .collect::<String>()
 }
 
-fn b(input: String) -> String {
-    let mut lines = input.split('\n').into_iter();
+fn parse_input(input: &str) -> (Vec<Stack>, Vec<Instruction>) {
+    let mut lines = input.split('\n');
 
-    let crate_lines: Vec<_> = (&mut lines).map_while(|line| {
-        all_consuming(parse_crate_line)(line).finish().ok().map(|(_, line)| line)
+    // everything up to the separator is the drawing, with the stack numbers on its last line
+    let drawing: Vec<_> = (&mut lines).take_while(|line| !line.is_empty()).collect();
+    let (footer, crate_lines) = drawing.split_last().expect("missing crate drawing");
+    let (_, centres) = parse_footer(footer).finish().expect("invalid stack numbers");
+
+    let crate_lines: Vec<_> = crate_lines.iter().map(|line| {
+        parse_crate_line(line, &centres).finish().expect("invalid crate line").1
     }).collect();
 
     // our result is by rows, but we need stacks, so transpose the Vec<Vec<Option<Crate>>>
-    let mut crate_stacks = transpose_rev(crate_lines);
-
-    lines.next(); // consume separator
+    let crate_stacks = transpose_rev(crate_lines).into_iter().map(Stack::from_iter).collect();
 
-    for instruction in lines
+    let instructions = lines
         .filter_map(|line| all_consuming(parse_instruction)(line).finish().ok())
-        .map(|instruction| instruction.1) {
-        let source_length = crate_stacks[instruction.src].len();
-        // could use `get_many_mut` to avoid heap allocation from `collect`
-        let substack = crate_stacks[instruction.src].drain(source_length - instruction.quantity..).collect_vec();
-        crate_stacks[instruction.dst].extend(substack)
+        .map(|instruction| instruction.1)
+        .collect();
+
+    (crate_stacks, instructions)
+}
+
+fn run(input: String, crane: Crane) -> String {
+    let (mut crate_stacks, instructions) = parse_input(&input);
+
+    for instruction in &instructions {
+        crane.execute(&mut crate_stacks, instruction);
     }
 
     crate_stacks
         .iter()
         .filter_map(|stack| stack.last())
-        .map(|c| c.0)
+        .map(|c| c.0.as_str())
         .collect::<String>()
 }
 
+fn a(input: String) -> String {
+    run(input, Crane::CrateMover9000)
+}
+
+fn b(input: String) -> String {
+    run(input, Crane::CrateMover9001)
+}
+
 #[test]
 fn test_example_a() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
@@ -169,4 +357,93 @@ fn test_example_a() {
 fn test_example_b() {
     let input = std::fs::read_to_string("src/example_data.txt").unwrap();
     assert_eq!(b(input), "MCD");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_wide_labels_and_many_stacks() {
+    let input = [
+        "                                               [J]",
+        "[AB]                                     [42]  [K]",
+        "[CD] [E] [F] [G] [H] [I] [Q] [R] [S]  [T] [UV] [W]",
+        " 1    2   3   4   5   6   7   8   9   10   11   12",
+        "",
+        "move 1 from 1 to 10",
+        "move 2 from 12 to 10",
+    ].join("\n");
+    assert_eq!(a(input.clone()), "CDEFGHIQRSK42W");
+    assert_eq!(b(input), "CDEFGHIQRSJ42W");
+}
+
+#[test]
+fn test_generated_puzzles() {
+    let config = PuzzleConfig { stacks: 12, crates: 40, moves: 100, max_label_len: 3 };
+
+    for seed in 0..20 {
+        let input = generate(seed, &config);
+        let (stacks, instructions) = parse_input(&input);
+        assert_eq!(instructions.len(), config.moves);
+
+        // the drawing should come back out exactly as it went in
+        let mut stacks = to_vecs(&stacks);
+        assert!(input.starts_with(&generate::draw(&stacks)));
+
+        // replay the moves one crate at a time, the slow way, to check the ropes against
+        let mut moved = stacks.clone();
+        for instruction in &instructions {
+            for _ in 0..instruction.quantity {
+                let c = moved[instruction.src].pop().unwrap();
+                moved[instruction.dst].push(c);
+            }
+            let source_length = stacks[instruction.src].len();
+            let substack = stacks[instruction.src].split_off(source_length - instruction.quantity);
+            stacks[instruction.dst].extend(substack);
+        }
+
+        let tops = |stacks: &[Vec<Crate>]| stacks.iter().filter_map(|s| s.last()).map(|c| c.0.clone()).collect::<String>();
+        assert_eq!(a(input.clone()), tops(&moved));
+        assert_eq!(b(input), tops(&stacks));
+    }
+}
+
+#[test]
+fn test_plan_example() {
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let (stacks, instructions) = parse_input(&input);
+    let start = to_vecs(&stacks);
+
+    for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+        let mut target = start.clone();
+        for instruction in &instructions {
+            crane.execute(&mut target, instruction);
+        }
+
+        // the puzzle's own four moves get there, so the shortest plan can't be any longer
+        let planned = plan(start.clone(), &Goal::Arrangement(target.clone()), crane, PLAN_STATE_LIMIT).unwrap();
+        assert!(planned.len() <= instructions.len());
+
+        let mut replayed = start.clone();
+        for instruction in &planned {
+            crane.execute(&mut replayed, instruction);
+        }
+        assert!(replayed == target);
+    }
+
+    let tops = Goal::Tops(vec![Crate("N".to_string()), Crate("D".to_string()), Crate("P".to_string())]);
+    assert!(plan(start, &tops, Crane::CrateMover9000, PLAN_STATE_LIMIT).unwrap().is_empty());
+}
+
+#[test]
+fn test_history_example() {
+    let input = std::fs::read_to_string("src/example_data.txt").unwrap();
+    let (stacks, instructions) = parse_input(&input);
+    let history = simulate_with_history(&stacks, &instructions, Crane::CrateMover9000);
+
+    let labels = |stack: &Vec<Crate>| stack.iter().map(|c| c.0.as_str()).collect::<String>();
+    assert_eq!(history.stacks.iter().map(labels).collect::<Vec<_>>(), ["C", "M", "PDNZ"]);
+
+    // D sits on top of stack 2, gets carried to stack 1 and then on to stack 3
+    let d = history.crates.iter().find(|c| c.label.0 == "D").unwrap();
+    assert_eq!(d.start, history::Position { stack: 1, height: 2 });
+    assert_eq!(d.end, history::Position { stack: 2, height: 1 });
+    assert_eq!(d.moves, [0, 1]);
+}
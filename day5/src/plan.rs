@@ -0,0 +1,96 @@
+// Working backwards from the answer: find the fewest instructions that get the crates from
+// one arrangement to another. Every move costs the same, so a breadth-first search over
+// arrangements finds a shortest plan.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use crate::{Crane, Crate, Instruction};
+
+type Arrangement = Vec<Vec<Crate>>;
+
+pub enum Goal {
+    // the crates on top of the non-empty stacks, read left to right, like the puzzle answer
+    Tops(Vec<Crate>),
+    // every stack exactly as given
+    Arrangement(Arrangement),
+}
+
+impl Goal {
+    fn is_reached(&self, stacks: &Arrangement) -> bool {
+        match self {
+            Goal::Tops(tops) => stacks.iter().filter_map(|stack| stack.last()).eq(tops.iter()),
+            Goal::Arrangement(target) => stacks == target,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PlanError {
+    Unreachable,
+    GaveUp(usize),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::Unreachable => write!(f, "no sequence of moves reaches the goal"),
+            PlanError::GaveUp(states) => write!(f, "gave up after exploring {} arrangements", states),
+        }
+    }
+}
+
+pub fn plan(start: Arrangement, goal: &Goal, crane: Crane, max_states: usize) -> Result<Vec<Instruction>, PlanError> {
+    if let Goal::Arrangement(target) = goal {
+        // moves never add, remove or relabel crates, so don't bother searching if they differ
+        let mut have: Vec<_> = start.iter().flatten().map(|c| &c.0).collect();
+        let mut want: Vec<_> = target.iter().flatten().map(|c| &c.0).collect();
+        have.sort();
+        want.sort();
+        if have != want || start.len() != target.len() {
+            return Err(PlanError::Unreachable);
+        }
+    }
+
+    // how we first reached each arrangement, so the plan can be read back from the goal
+    let mut came_from: HashMap<Arrangement, Option<(Arrangement, Instruction)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    came_from.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(stacks) = queue.pop_front() {
+        if goal.is_reached(&stacks) {
+            return Ok(backtrack(&came_from, stacks));
+        }
+
+        for src in 0..stacks.len() {
+            for dst in (0..stacks.len()).filter(|&dst| dst != src) {
+                for quantity in 1..=stacks[src].len() {
+                    let instruction = Instruction { quantity, src, dst };
+                    let mut next = stacks.clone();
+                    crane.execute(&mut next, &instruction);
+
+                    if !came_from.contains_key(&next) {
+                        if came_from.len() >= max_states {
+                            return Err(PlanError::GaveUp(came_from.len()));
+                        }
+                        came_from.insert(next.clone(), Some((stacks.clone(), instruction)));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+    }
+
+    Err(PlanError::Unreachable)
+}
+
+fn backtrack(came_from: &HashMap<Arrangement, Option<(Arrangement, Instruction)>>, mut stacks: Arrangement) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    while let Some((previous, instruction)) = &came_from[&stacks] {
+        instructions.push(instruction.clone());
+        stacks = previous.clone();
+    }
+    instructions.reverse();
+    instructions
+}
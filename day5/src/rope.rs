@@ -0,0 +1,213 @@
+// An implicit treap: a balanced binary tree keyed by position rather than value.
+// Splitting and joining at any position is O(log n), and reversal is applied
+// lazily, so moving a block of crates costs the same no matter how big it is.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    priority: u64,
+    size: usize,
+    // the children of this node still need to be swapped (recursively)
+    reversed: bool,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+fn next_priority() -> u64 {
+    // splitmix64 over a global counter, good enough to keep the tree balanced
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut z = COUNTER.fetch_add(0x9e3779b97f4a7c15, Ordering::Relaxed);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+fn push_down<T>(node: &mut Node<T>) {
+    if node.reversed {
+        std::mem::swap(&mut node.left, &mut node.right);
+        for child in [&mut node.left, &mut node.right].into_iter().flatten() {
+            child.reversed ^= true;
+        }
+        node.reversed = false;
+    }
+}
+
+fn update<T>(node: &mut Node<T>) {
+    node.size = 1 + size(&node.left) + size(&node.right);
+}
+
+// split into the first `at` elements and the rest
+fn split<T>(link: Link<T>, at: usize) -> (Link<T>, Link<T>) {
+    match link {
+        None => (None, None),
+        Some(mut node) => {
+            push_down(&mut node);
+            let left_size = size(&node.left);
+            if at <= left_size {
+                let (left, right) = split(node.left.take(), at);
+                node.left = right;
+                update(&mut node);
+                (left, Some(node))
+            } else {
+                let (left, right) = split(node.right.take(), at - left_size - 1);
+                node.right = left;
+                update(&mut node);
+                (Some(node), right)
+            }
+        }
+    }
+}
+
+fn merge<T>(left: Link<T>, right: Link<T>) -> Link<T> {
+    match (left, right) {
+        (None, link) | (link, None) => link,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                push_down(&mut left);
+                left.right = merge(left.right.take(), Some(right));
+                update(&mut left);
+                Some(left)
+            } else {
+                push_down(&mut right);
+                right.left = merge(Some(left), right.left.take());
+                update(&mut right);
+                Some(right)
+            }
+        }
+    }
+}
+
+pub struct Rope<T> {
+    root: Link<T>,
+}
+
+impl<T> Rope<T> {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn push(&mut self, value: T) {
+        let node = Node { value, priority: next_priority(), size: 1, reversed: false, left: None, right: None };
+        self.root = merge(self.root.take(), Some(Box::new(node)));
+    }
+
+    /// Splits off everything from position `at` onwards, like `Vec::split_off`.
+    pub fn split_off(&mut self, at: usize) -> Rope<T> {
+        assert!(at <= self.len(), "split position out of bounds");
+        let (left, right) = split(self.root.take(), at);
+        self.root = left;
+        Rope { root: right }
+    }
+
+    pub fn append(&mut self, other: Rope<T>) {
+        self.root = merge(self.root.take(), other.root);
+    }
+
+    pub fn reverse(&mut self) {
+        if let Some(root) = &mut self.root {
+            root.reversed ^= true;
+        }
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        let mut node = self.root.as_ref()?;
+        let mut flip = false;
+        loop {
+            flip ^= node.reversed;
+            match if flip { &node.left } else { &node.right } {
+                Some(next) => node = next,
+                None => return Some(&node.value),
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_spine(&self.root, false);
+        iter
+    }
+}
+
+impl<T> Default for Rope<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for Rope<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut rope = Rope::new();
+        for value in iter {
+            rope.push(value);
+        }
+        rope
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Rope<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+pub struct Iter<'a, T> {
+    // nodes still to visit, along with whether their subtree is read back to front
+    stack: Vec<(&'a Node<T>, bool)>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_spine(&mut self, mut link: &'a Link<T>, mut flip: bool) {
+        while let Some(node) = link {
+            flip ^= node.reversed;
+            self.stack.push((node, flip));
+            link = if flip { &node.right } else { &node.left };
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, flip) = self.stack.pop()?;
+        self.push_spine(if flip { &node.left } else { &node.right }, flip);
+        Some(&node.value)
+    }
+}
+
+#[test]
+fn test_rope_matches_vec() {
+    let mut rope: Rope<usize> = (0..100).collect();
+    let mut vec: Vec<usize> = (0..100).collect();
+
+    // shuffle blocks around the same way on both and compare after each step
+    for step in 0..200 {
+        let at = (step * 37) % (vec.len() + 1);
+        let mut rope_tail = rope.split_off(at);
+        let mut vec_tail = vec.split_off(at);
+        if step % 3 == 0 {
+            rope_tail.reverse();
+            vec_tail.reverse();
+        }
+        rope_tail.append(rope);
+        vec_tail.append(&mut vec);
+        rope = rope_tail;
+        vec = vec_tail;
+
+        assert_eq!(rope.len(), vec.len());
+        assert_eq!(rope.last(), vec.last());
+        assert!(rope.iter().eq(vec.iter()));
+    }
+}